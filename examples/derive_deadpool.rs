@@ -1,9 +1,6 @@
 use deadpool_redis::{
-    // Very important to import inner redis - otherwise macro expansion fails!
-    redis,
     redis::{AsyncCommands, ErrorKind, RedisError, RedisResult},
-    Config,
-    Runtime,
+    Config, Runtime,
 };
 use redis_macros::{FromRedisValue, ToRedisArgs};
 use serde::{Deserialize, Serialize};
@@ -18,7 +15,9 @@ enum Address {
 }
 
 /// Don't forget to implement serde traits and redis traits!
+/// The traits are implemented for the redis crate reexported by deadpool-redis
 #[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+#[redis_crate = "deadpool_redis::redis"]
 struct User {
     id: u32,
     name: String,
@@ -1,5 +1,5 @@
 use redis::{Client, ErrorKind, JsonAsyncCommands, RedisError, RedisResult};
-use redis_macros::Json;
+use redis_macros::{Json, JsonAll, JsonType, PerMatch};
 use serde::{Deserialize, Serialize};
 
 /// Define structs to hold the data
@@ -51,21 +51,23 @@ async fn main() -> RedisResult<()> {
     let Json(stored_name): Json<String> = con.json_get("user_wrapped_modify", "$.name").await?;
     assert_eq!("Bowie", stored_name);
 
-    // Increment numbers with JSON.NUMINCRBY
-    let _: () = con
+    // Increment numbers with JSON.NUMINCRBY, which returns the new values
+    let JsonAll(new_ids): JsonAll<Option<u32>> = con
         .json_num_incr_by("user_wrapped_modify", "$.id", 1)
         .await?;
+    assert_eq!(vec![Some(2)], new_ids);
     let Json(stored_id): Json<u32> = con.json_get("user_wrapped_modify", "$.id").await?;
     assert_eq!(2, stored_id);
 
-    // Append item to array with JSON.ARR_APPEND
-    let _: () = con
+    // Append item to array with JSON.ARR_APPEND, which returns the new lengths
+    let PerMatch(lengths): PerMatch<Option<usize>> = con
         .json_arr_append(
             "user_wrapped_modify",
             "$.addresses",
             &Address::Street("Oxford".to_string()),
         )
         .await?;
+    assert_eq!(vec![Some(3)], lengths);
     let Json(stored_addresses): Json<Vec<Address>> =
         con.json_get("user_wrapped_modify", "$.addresses").await?;
     assert_eq!(
@@ -77,6 +79,13 @@ async fn main() -> RedisResult<()> {
         stored_addresses
     );
 
+    // Check the types of the values with JSON.TYPE
+    let PerMatch(types): PerMatch<JsonType> = con.json_type("user_wrapped_modify", "$.*").await?;
+    assert_eq!(
+        vec![JsonType::Integer, JsonType::String, JsonType::Array],
+        types
+    );
+
     Ok(())
 }
 
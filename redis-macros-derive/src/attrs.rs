@@ -0,0 +1,527 @@
+//! Parse the attributes of the derive macros.
+
+use crate::serde_names::{self, Direction};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::spanned::Spanned;
+use syn::{
+    ext::IdentExt, parse::ParseStream, punctuated::Punctuated, Attribute, Data, DataStruct,
+    DataUnion, Expr, ExprLit, Field, Fields, GenericParam, Generics, Ident, Lit, LitInt, LitStr,
+    Meta, MetaList, MetaNameValue, Path, Token, WhereClause, WherePredicate,
+};
+
+/// The serializer and the fallback serializers from `#[redis_serializer(serde_json, fallback(serde_yaml))]`.
+pub struct Serializers {
+    pub primary: TokenStream2,
+    pub fallbacks: Vec<TokenStream2>,
+}
+
+fn parse_serializers(input: ParseStream) -> syn::Result<Serializers> {
+    let primary = match input.parse::<Expr>()? {
+        Expr::Path(primary) => primary,
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => {
+            return Err(syn::Error::new_spanned(
+                &lit,
+                format!(
+                    "expected serializer path, remove the quotes: `{}`",
+                    lit.value()
+                ),
+            ))
+        }
+        other => return Err(syn::Error::new_spanned(other, "expected serializer path")),
+    };
+    let mut fallbacks = Vec::new();
+    if input.parse::<Option<Token![,]>>()?.is_some() {
+        let meta = input.parse::<MetaList>()?;
+        if !meta.path.is_ident("fallback") {
+            return Err(syn::Error::new_spanned(meta.path, "expected `fallback`"));
+        }
+        let paths = meta.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)?;
+        fallbacks.extend(paths.iter().map(ToTokens::to_token_stream));
+    }
+    Ok(Serializers {
+        primary: primary.to_token_stream(),
+        fallbacks,
+    })
+}
+
+pub fn get_serializers(attrs: &[Attribute], default: &str) -> syn::Result<Serializers> {
+    match find_attr(attrs, "redis_serializer")? {
+        Some(attr) => attr.parse_args_with(parse_serializers),
+        None => Ok(Serializers {
+            primary: default.parse::<TokenStream2>().unwrap(),
+            fallbacks: Vec::new(),
+        }),
+    }
+}
+
+pub fn get_serializer(attrs: &[Attribute], default: &str) -> syn::Result<TokenStream2> {
+    get_serializers(attrs, default).map(|serializers| serializers.primary)
+}
+
+/// Return the attribute with the name, failing if it is set more than once.
+fn find_attr<'a>(attrs: &'a [Attribute], name: &str) -> syn::Result<Option<&'a Attribute>> {
+    let mut found = attrs.iter().filter(|attr| attr.path().is_ident(name));
+    let first = found.next();
+    match found.next() {
+        Some(duplicate) => Err(syn::Error::new_spanned(
+            duplicate,
+            format!("duplicate {name} attribute"),
+        )),
+        None => Ok(first),
+    }
+}
+
+/// Reject unions, which cannot be serialized with serde.
+pub fn forbid_union(data: &Data, derive: &str) -> syn::Result<()> {
+    match data {
+        Data::Union(DataUnion { union_token, .. }) => Err(syn::Error::new_spanned(
+            union_token,
+            format!("{derive} cannot be derived for unions"),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Return the `redis_macros::Envelope` constant of the format id for the known serializers.
+pub fn get_format_id(
+    serializer: &TokenStream2,
+    redis_macros: &TokenStream2,
+) -> Option<TokenStream2> {
+    let name = serializer.to_string();
+    let format = match name.rsplit("::").next().map(str::trim) {
+        Some("serde_json") => quote! { FORMAT_JSON },
+        Some("serde_yaml") => quote! { FORMAT_YAML },
+        Some("toml") => quote! { FORMAT_TOML },
+        Some("ron") => quote! { FORMAT_RON },
+        _ => return None,
+    };
+    Some(quote! { #redis_macros::Envelope::#format })
+}
+
+/// Return the schema id from `#[redis_envelope]` or `#[redis_envelope(schema = 1)]`, if the values should be written with an envelope.
+pub fn get_envelope(attrs: &[Attribute]) -> syn::Result<Option<LitInt>> {
+    let Some(attr) = find_attr(attrs, "redis_envelope")? else {
+        return Ok(None);
+    };
+    let mut schema = LitInt::new("0", attr.span());
+    if let Meta::List(_) = attr.meta {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("schema") {
+                schema = meta.value()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported redis_envelope option, expected `schema`"))
+            }
+        })?;
+    }
+    Ok(Some(schema))
+}
+
+/// Return the `#[redis_compress]` attribute, if the values should be compressed (which also writes them with an envelope).
+pub fn get_compress(attrs: &[Attribute]) -> syn::Result<Option<&Attribute>> {
+    let Some(attr) = find_attr(attrs, "redis_compress")? else {
+        return Ok(None);
+    };
+    attr.meta.require_path_only()?;
+    Ok(Some(attr))
+}
+
+/// Return the template from `#[redis_key = "user:{id}"]`, if the type has a key template.
+pub fn get_key_template(attrs: &[Attribute]) -> syn::Result<Option<LitStr>> {
+    let Some(attr) = find_attr(attrs, "redis_key")? else {
+        return Ok(None);
+    };
+    match &attr.meta {
+        Meta::NameValue(MetaNameValue {
+            value:
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(template),
+                    ..
+                }),
+            ..
+        }) => Ok(Some(template.clone())),
+        _ => Err(syn::Error::new_spanned(
+            attr,
+            "expected #[redis_key = \"prefix:{field}\"]",
+        )),
+    }
+}
+
+/// How to handle the square brackets of the RedisJSON responses, set with `#[redis_json(brackets = "...")]`.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Brackets {
+    /// Try to deserialize the response, and retry without the brackets if it fails
+    Auto,
+    /// Deserialize responses starting with a bracket as RedisJSON responses with exactly one match
+    Strict,
+    /// Only accept RedisJSON responses with exactly one match
+    Required,
+    /// Never remove the brackets
+    Off,
+}
+
+/// Return the bracket handling from `#[redis_json(brackets = "...")]`, defaulting to [`Brackets::Auto`].
+pub fn get_json_brackets(attrs: &[Attribute]) -> syn::Result<(Brackets, Option<&Attribute>)> {
+    let mut brackets = Brackets::Auto;
+    let attr = find_attr(attrs, "redis_json")?;
+    if let Some(attr) = attr {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("brackets") {
+                return Err(meta.error("unsupported redis_json option, expected `brackets`"));
+            }
+            let value = meta.value()?.parse::<LitStr>()?;
+            brackets = match value.value().as_str() {
+                "auto" => Brackets::Auto,
+                "strict" => Brackets::Strict,
+                "required" => Brackets::Required,
+                "off" => Brackets::Off,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        value,
+                        "expected one of \"auto\", \"strict\", \"required\" or \"off\"",
+                    ))
+                }
+            };
+            Ok(())
+        })?;
+    }
+    Ok((brackets, attr))
+}
+
+/// Return the validator functions from the `#[redis_validate(path::to::fn)]` attributes.
+pub fn get_validators(attrs: &[Attribute]) -> syn::Result<Vec<Path>> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("redis_validate"))
+        .map(|attr| attr.parse_args::<Path>())
+        .collect()
+}
+
+/// Return the `redis_macros::Redaction` from `#[redis_errors(redact_payload)]` and the `#[redis_redact]` fields.
+pub fn get_redaction(attrs: &[Attribute], data: &Data) -> syn::Result<TokenStream2> {
+    let redis_macros = get_redis_macros_crate(attrs)?;
+    if redacts_payload(attrs)? {
+        return Ok(quote! { #redis_macros::Redaction::Payload });
+    }
+
+    let mut redacted = Vec::new();
+    match data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => {
+            let rename_all = serde_names::rename_all(attrs);
+            for field in &fields.named {
+                if !is_redacted(field)? {
+                    continue;
+                }
+                // Also hide the field if it is in the response with its Rust name
+                let name = serde_names::field_name(field, rename_all.as_deref());
+                let ident = field.ident.as_ref().unwrap().unraw().to_string();
+                if name != ident {
+                    redacted.push(ident);
+                }
+                redacted.push(name);
+            }
+        }
+        Data::Struct(data) => forbid_redact(data.fields.iter())?,
+        Data::Enum(data) => forbid_redact(data.variants.iter().flat_map(|v| v.fields.iter()))?,
+        Data::Union(_) => {}
+    }
+
+    if redacted.is_empty() {
+        Ok(quote! { #redis_macros::Redaction::None })
+    } else {
+        Ok(quote! { #redis_macros::Redaction::Fields(&[#(#redacted),*]) })
+    }
+}
+
+/// Return whether the whole response is hidden from the errors, with `#[redis_errors(redact_payload)]`.
+pub fn redacts_payload(attrs: &[Attribute]) -> syn::Result<bool> {
+    let mut redact_payload = false;
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("redis_errors"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("redact_payload") {
+                redact_payload = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported redis_errors option, expected `redact_payload`"))
+            }
+        })?;
+    }
+    Ok(redact_payload)
+}
+
+/// Return whether the field is marked with `#[redis_redact]`.
+pub fn is_redacted(field: &Field) -> syn::Result<bool> {
+    match find_attr(&field.attrs, "redis_redact")? {
+        Some(attr) => attr.meta.require_path_only().map(|_| true),
+        None => Ok(false),
+    }
+}
+
+fn forbid_redact<'a>(fields: impl Iterator<Item = &'a Field>) -> syn::Result<()> {
+    for field in fields {
+        if let Some(attr) = find_attr(&field.attrs, "redis_redact")? {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "redis_redact is only supported on named struct fields, use #[redis_errors(redact_payload)] instead",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Return the JSONPath from the `#[json_path("$.path")]` attribute of the field.
+pub fn get_json_path(field: &Field) -> syn::Result<LitStr> {
+    find_attr(&field.attrs, "json_path")?
+        .ok_or_else(|| syn::Error::new_spanned(field, "missing #[json_path(\"...\")] attribute"))?
+        .parse_args::<LitStr>()
+}
+
+/// Return true if the field is marked with `#[redis_path(leaf)]`.
+pub fn is_path_leaf(field: &Field) -> syn::Result<bool> {
+    let mut leaf = false;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("redis_path"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("leaf") {
+                leaf = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported redis_path option, expected `leaf`"))
+            }
+        })?;
+    }
+    Ok(leaf)
+}
+
+/// Return the `#[redis_transparent]` attribute, if the type delegates to its single field.
+pub fn get_transparent(attrs: &[Attribute]) -> syn::Result<Option<&Attribute>> {
+    let Some(attr) = find_attr(attrs, "redis_transparent")? else {
+        return Ok(None);
+    };
+    attr.meta.require_path_only()?;
+    forbid_serializer_options(attrs, "redis_transparent")?;
+    Ok(Some(attr))
+}
+
+/// The scalar encoding of a fieldless enum, set with `#[redis_enum(...)]`.
+pub enum EnumEncoding {
+    /// The name of the variant, from `#[redis_enum(as_str)]`
+    AsStr,
+    /// The discriminant of the variant, from `#[redis_enum(repr = u8)]`
+    Repr(Ident),
+}
+
+const INTEGER_TYPES: &[&str] = &[
+    "u8", "u16", "u32", "u64", "usize", "i8", "i16", "i32", "i64", "isize",
+];
+
+/// Return the encoding from `#[redis_enum(as_str)]` or `#[redis_enum(repr = u8)]`, if there is one.
+pub fn get_enum_encoding(attrs: &[Attribute]) -> syn::Result<Option<(EnumEncoding, &Attribute)>> {
+    let Some(attr) = find_attr(attrs, "redis_enum")? else {
+        return Ok(None);
+    };
+    let mut encoding = None;
+    attr.parse_nested_meta(|meta| {
+        if encoding.is_some() {
+            return Err(meta.error("redis_enum expects a single option"));
+        }
+        if meta.path.is_ident("as_str") {
+            encoding = Some(EnumEncoding::AsStr);
+            Ok(())
+        } else if meta.path.is_ident("repr") {
+            let repr = meta.value()?.parse::<Ident>()?;
+            if !INTEGER_TYPES.iter().any(|ty| repr == ty) {
+                return Err(syn::Error::new_spanned(repr, "expected an integer type"));
+            }
+            encoding = Some(EnumEncoding::Repr(repr));
+            Ok(())
+        } else {
+            Err(meta.error("unsupported redis_enum option, expected `as_str` or `repr`"))
+        }
+    })?;
+    let encoding =
+        encoding.ok_or_else(|| syn::Error::new_spanned(attr, "expected `as_str` or `repr`"))?;
+    forbid_serializer_options(attrs, "redis_enum")?;
+    Ok(Some((encoding, attr)))
+}
+
+/// Return the `#[redis_reply(tuple)]` attribute, if the fields are read from the elements of an array response.
+pub fn get_reply_tuple(attrs: &[Attribute]) -> syn::Result<Option<&Attribute>> {
+    let Some(attr) = find_attr(attrs, "redis_reply")? else {
+        return Ok(None);
+    };
+    let mut tuple = false;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("tuple") {
+            tuple = true;
+            Ok(())
+        } else {
+            Err(meta.error("unsupported redis_reply option, expected `tuple`"))
+        }
+    })?;
+    if !tuple {
+        return Err(syn::Error::new_spanned(attr, "expected `tuple`"));
+    }
+    forbid_serializer_options(attrs, "redis_reply")?;
+    Ok(Some(attr))
+}
+
+/// Reject the attributes configuring the serializer, for the types which are not serialized with serde.
+fn forbid_serializer_options(attrs: &[Attribute], name: &str) -> syn::Result<()> {
+    let options = [
+        "redis_serializer",
+        "redis_envelope",
+        "redis_compress",
+        "redis_json",
+        "redis_errors",
+        "redis_validate",
+        "redis_transparent",
+        "redis_enum",
+        "redis_reply",
+        "serde_json_crate",
+    ];
+    match attrs.iter().find(|attr| {
+        !attr.path().is_ident(name) && options.iter().any(|option| attr.path().is_ident(option))
+    }) {
+        Some(other) => Err(syn::Error::new_spanned(
+            other,
+            format!("{name} cannot be combined with other redis attributes"),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Return the bounds from `#[redis_bound(deserialize = "...", serialize = "...")]` for the direction, if they are set.
+pub fn get_bounds(
+    attrs: &[Attribute],
+    direction: Direction,
+) -> syn::Result<Option<Vec<WherePredicate>>> {
+    let mut bounds = None;
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("redis_bound"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("deserialize") && !meta.path.is_ident("serialize") {
+                return Err(meta.error(
+                    "unsupported redis_bound option, expected `deserialize` or `serialize`",
+                ));
+            }
+            let value = meta.value()?.parse::<LitStr>()?;
+            if meta.path.is_ident(direction.key()) {
+                let predicates =
+                    value.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+                bounds = Some(predicates.into_iter().collect());
+            }
+            Ok(())
+        })?;
+    }
+    Ok(bounds)
+}
+
+/// Return the `bound` for each type parameter (e.g. `T: ::serde::Serialize`).
+pub fn type_param_bounds(generics: &Generics, bound: TokenStream2) -> Vec<WherePredicate> {
+    generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(type_param) => {
+                let ident = &type_param.ident;
+                Some(syn::parse_quote! { #ident : #bound })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Return the where clause of the type, extended with the custom bounds if they are set, or the default bounds.
+pub fn where_clause(
+    generics: &Generics,
+    custom: Option<Vec<WherePredicate>>,
+    default: Vec<WherePredicate>,
+) -> Option<WhereClause> {
+    let mut where_clause = generics.where_clause.clone();
+    for predicate in custom.unwrap_or(default) {
+        match where_clause {
+            Some(ref mut w) => w.predicates.push(predicate),
+            None => where_clause = Some(syn::parse_quote! { where #predicate }),
+        }
+    }
+    where_clause
+}
+
+/// Return the path of a crate from `#[name = "path::to::crate"]`, if it is set.
+fn get_crate_path(attrs: &[Attribute], name: &str) -> syn::Result<Option<Path>> {
+    let Some(attr) = find_attr(attrs, name)? else {
+        return Ok(None);
+    };
+    match &attr.meta {
+        Meta::NameValue(MetaNameValue {
+            value:
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(path),
+                    ..
+                }),
+            ..
+        }) => path.parse().map(Some),
+        _ => Err(syn::Error::new_spanned(
+            attr,
+            format!("expected #[{name} = \"path::to::crate\"]"),
+        )),
+    }
+}
+
+/// Return the path of the redis_macros crate from `#[redis_macros_crate = "..."]`, defaulting to `::redis_macros`.
+pub fn get_redis_macros_crate(attrs: &[Attribute]) -> syn::Result<TokenStream2> {
+    Ok(match get_crate_path(attrs, "redis_macros_crate")? {
+        Some(path) => path.to_token_stream(),
+        None => quote! { ::redis_macros },
+    })
+}
+
+/// Return the path of the redis crate from `#[redis_crate = "..."]`, defaulting to the re-export of `redis_macros`.
+pub fn get_redis_crate(attrs: &[Attribute]) -> syn::Result<TokenStream2> {
+    Ok(match get_crate_path(attrs, "redis_crate")? {
+        Some(path) => path.to_token_stream(),
+        None => {
+            let redis_macros = get_redis_macros_crate(attrs)?;
+            quote! { #redis_macros::__private::redis }
+        }
+    })
+}
+
+/// Return true if `#[redis_crate = "..."]` is not set, so the derives use the redis crate of `redis_macros`.
+pub fn has_default_redis_crate(attrs: &[Attribute]) -> syn::Result<bool> {
+    Ok(get_crate_path(attrs, "redis_crate")?.is_none())
+}
+
+/// Return the statement converting the response `v` to the `Value` of `redis_macros`, if `#[redis_crate = "..."]` is set.
+///
+/// The responses are passed to the helpers of `redis_macros`, which only accept the `Value` of its own redis crate,
+/// so it fails to compile with a clear error unless the path reexports the same version of redis.
+pub fn redis_macros_value(attrs: &[Attribute]) -> syn::Result<TokenStream2> {
+    Ok(match get_crate_path(attrs, "redis_crate")? {
+        Some(_) => {
+            let redis_macros = get_redis_macros_crate(attrs)?;
+            quote! { let v = #redis_macros::__private::redis_macros_value(v); }
+        }
+        None => quote! {},
+    })
+}
+
+/// Return the path of the serde_json crate from `#[serde_json_crate = "..."]`, if it replaces the JSON engine.
+pub fn get_serde_json_crate(attrs: &[Attribute]) -> syn::Result<Option<Path>> {
+    get_crate_path(attrs, "serde_json_crate")
+}
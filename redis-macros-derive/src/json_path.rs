@@ -0,0 +1,160 @@
+//! Generate the code of `#[derive(RedisJsonPath)]`.
+
+use crate::attrs::{
+    self, get_bounds, get_redis_crate, get_redis_macros_crate, is_path_leaf, type_param_bounds,
+};
+use crate::serde_names::{self, Direction};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Data, DataStruct, DeriveInput, Fields};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let DeriveInput {
+        ident,
+        vis,
+        attrs,
+        generics,
+        data,
+    } = input;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let redis_macros = get_redis_macros_crate(&attrs)?;
+
+    let fields = match data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => fields,
+        // Enums can only be used as a whole
+        Data::Enum(_) => {
+            return Ok(quote! {
+                impl #impl_generics #redis_macros::HasJsonPath for #ident #ty_generics #where_clause {
+                    type Path = #redis_macros::JsonPath<Self>;
+
+                    fn json_path(path: #redis_macros::JsonPath<Self>) -> Self::Path {
+                        path
+                    }
+                }
+            });
+        }
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "RedisJsonPath is only supported on structs with named fields and enums",
+            ))
+        }
+    };
+
+    let path_ident = format_ident!("{}Path", ident);
+    let redis = get_redis_crate(&attrs)?;
+    let path_doc = format!("The JSONPath accessors of [`{}`].", ident);
+    let rename_all = serde_names::rename_all_for(&attrs, Direction::Serialize);
+
+    let mut accessors = Vec::new();
+    for field in &fields.named {
+        if serde_names::has_flag(&field.attrs, &["skip", "skip_serializing"]) {
+            continue;
+        }
+        let field_ident = &field.ident;
+        let field_vis = &field.vis;
+        let ty = &field.ty;
+        let doc = format!("The path of the `{}` field.", field_ident.as_ref().unwrap());
+        let accessor = if serde_names::has_flag(&field.attrs, &["flatten"]) {
+            // Flattened fields are in the same object
+            quote! {
+                #field_vis fn #field_ident(&self) -> <#ty as #redis_macros::HasJsonPath>::Path {
+                    <#ty as #redis_macros::HasJsonPath>::json_path(::std::clone::Clone::clone(&self.0).cast_unchecked())
+                }
+            }
+        } else {
+            let name =
+                serde_names::field_name_for(field, rename_all.as_deref(), Direction::Serialize);
+            if is_path_leaf(field)? {
+                quote! {
+                    #field_vis fn #field_ident(&self) -> #redis_macros::JsonPath<#ty> {
+                        self.0.member_unchecked(#name)
+                    }
+                }
+            } else {
+                quote! {
+                    #field_vis fn #field_ident(&self) -> <#ty as #redis_macros::HasJsonPath>::Path {
+                        <#ty as #redis_macros::HasJsonPath>::json_path(self.0.member_unchecked(#name))
+                    }
+                }
+            }
+        };
+        accessors.push(quote! {
+            #[doc = #doc]
+            #accessor
+        });
+    }
+
+    // The accessors of the generic fields need the paths of the type parameters, unless they are set with `#[redis_bound]`
+    let bounds = get_bounds(&attrs, Direction::Serialize)?;
+    let defaults = type_param_bounds(&generics, quote! { #redis_macros::HasJsonPath });
+    let where_with_path = attrs::where_clause(&generics, bounds, defaults);
+
+    Ok(quote! {
+        #[doc = #path_doc]
+        #vis struct #path_ident #generics (#redis_macros::JsonPath<#ident #ty_generics>) #where_clause;
+
+        impl #impl_generics #path_ident #ty_generics #where_with_path {
+            #(#accessors)*
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// The JSONPath of the root of the document, to access the paths of the fields.
+            #vis fn path() -> #path_ident #ty_generics {
+                #path_ident(#redis_macros::JsonPath::root())
+            }
+        }
+
+        impl #impl_generics #redis_macros::HasJsonPath for #ident #ty_generics #where_clause {
+            type Path = #path_ident #ty_generics;
+
+            fn json_path(path: #redis_macros::JsonPath<Self>) -> Self::Path {
+                #path_ident(path)
+            }
+        }
+
+        impl #impl_generics #redis_macros::TypedJsonPath for #path_ident #ty_generics #where_clause {
+            type Target = #ident #ty_generics;
+        }
+
+        impl #impl_generics ::std::ops::Deref for #path_ident #ty_generics #where_clause {
+            type Target = #redis_macros::JsonPath<#ident #ty_generics>;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<#path_ident #ty_generics> for #redis_macros::JsonPath<#ident #ty_generics> #where_clause {
+            fn from(path: #path_ident #ty_generics) -> Self {
+                path.0
+            }
+        }
+
+        impl #impl_generics ::std::clone::Clone for #path_ident #ty_generics #where_clause {
+            fn clone(&self) -> Self {
+                #path_ident(::std::clone::Clone::clone(&self.0))
+            }
+        }
+
+        impl #impl_generics ::std::fmt::Debug for #path_ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::fmt::Debug::fmt(&self.0, f)
+            }
+        }
+
+        impl #impl_generics #redis::ToRedisArgs for #path_ident #ty_generics #where_clause {
+            fn write_redis_args<W>(&self, out: &mut W)
+            where
+                W: ?::std::marker::Sized + #redis::RedisWrite,
+            {
+                #redis::ToRedisArgs::write_redis_args(&self.0, out)
+            }
+        }
+
+        impl #impl_generics #redis::ToSingleRedisArg for #path_ident #ty_generics #where_clause {}
+    })
+}
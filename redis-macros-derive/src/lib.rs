@@ -1,23 +1,24 @@
+mod attrs;
+mod json_path;
+mod projection;
+mod redis_key;
+mod redis_value;
+mod scalar_enum;
+mod serde_names;
+mod transparent;
+mod tuple_reply;
+
+use attrs::{
+    forbid_union, get_bounds, get_compress, get_enum_encoding, get_envelope, get_format_id,
+    get_json_brackets, get_redaction, get_redis_crate, get_redis_macros_crate, get_reply_tuple,
+    get_serde_json_crate, get_serializer, get_serializers, get_transparent, get_validators,
+    has_default_redis_crate, redis_macros_value, type_param_bounds, where_clause, Brackets,
+};
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
-use quote::{quote, ToTokens};
-use syn::{parse_macro_input, Attribute, DeriveInput, Expr, GenericParam};
-
-fn get_serializer(attrs: Vec<Attribute>, default: &str) -> TokenStream2 {
-    let default_token = default.parse::<TokenStream2>().unwrap();
-
-    attrs
-        .into_iter()
-        .find(|attr| attr.path().is_ident("redis_serializer"))
-        .and_then(|attr| {
-            let Ok(Expr::Path(path)) = attr.parse_args::<Expr>() else {
-                return None;
-            };
-
-            Some(path.to_token_stream())
-        })
-        .unwrap_or(default_token)
-}
+use quote::quote;
+use serde_names::Direction;
+use syn::{parse_macro_input, spanned::Spanned, DeriveInput};
 
 /// Derive macro for the redis crate's [`FromRedisValue`](../redis/trait.FromRedisValue.html) trait to allow parsing Redis responses to this type.
 ///
@@ -55,90 +56,422 @@ fn get_serializer(attrs: Vec<Attribute>, default: &str) -> TokenStream2 {
 /// struct User { id: u32 }
 /// ```
 ///
+/// If the response cannot be deserialized, the error contains the serde error with the path to the failing field
+/// and a truncated preview of the response (see `redis_macros::DeserializeError`).
+///
+/// With the `simd-json` or `sonic-rs` features of `redis_macros`, `serde_json` is replaced by the faster engine
+/// (see `redis_macros::JsonEngine`), with the same semantics and error messages.
+///
+/// When using `serde_json`, the response is retried without the square brackets if it fails, to support RedisJSON commands.
+/// You can configure this with the `redis_json` attribute:
+///
+/// - `#[redis_json(brackets = "auto")]`: try the response as is, then retry without the brackets (default),
+/// - `#[redis_json(brackets = "strict")]`: responses in brackets are RedisJSON responses, which must have exactly one match,
+/// - `#[redis_json(brackets = "required")]`: only accept RedisJSON responses with exactly one match (e.g. `JSON.GET key $`),
+/// - `#[redis_json(brackets = "off")]`: never remove the brackets, and reject them for structs (e.g. for types only used with `GET`).
+///
+/// ```rust
+/// use redis_macros::{FromRedisValue};
+/// use serde::{Deserialize};
+///
+/// #[derive(FromRedisValue, Deserialize)]
+/// #[redis_json(brackets = "required")]
+/// struct User { id: u32 }
+/// ```
+///
+/// To migrate between serializers, you can add fallback serializers, which are tried in order if the first one fails.
+/// The values are always written with the first serializer, and you can use the `redis_macros::WithFormat` wrapper
+/// to find out which serializer deserialized a value.
+///
+/// ```rust
+/// use redis_macros::{FromRedisValue};
+/// use serde::{Deserialize};
+///
+/// #[derive(FromRedisValue, Deserialize)]
+/// #[redis_serializer(serde_json, fallback(serde_yaml))]
+/// struct User { id: u32 }
+/// ```
+///
+/// To keep sensitive data out of the errors (and your logs), mark the fields with `#[redis_redact]`, or the whole type
+/// with `#[redis_errors(redact_payload)]` to hide the response entirely. The fields with the same names are also
+/// hidden in nested objects, and their values are removed from the messages of the validators.
+///
+/// ```rust
+/// use redis_macros::{FromRedisValue};
+/// use serde::{Deserialize};
+///
+/// #[derive(FromRedisValue, Deserialize)]
+/// struct User {
+///     id: u32,
+///     #[redis_redact]
+///     password: String,
+/// }
+/// ```
+///
+/// If the data might violate some invariants, you can add validators with `#[redis_validate(path::to::fn)]`.
+/// The validator takes a reference to the value and returns `Result<(), E>`, where `E` implements `Display`.
+/// It runs after the value was deserialized, and its error is returned as the parsing error.
+///
+/// ```rust
+/// use redis_macros::{FromRedisValue};
+/// use serde::{Deserialize};
+///
+/// fn non_negative(account: &Account) -> Result<(), &'static str> {
+///     if account.balance < 0 {
+///         return Err("balance is negative");
+///     }
+///     Ok(())
+/// }
+///
+/// #[derive(FromRedisValue, Deserialize)]
+/// #[redis_validate(non_negative)]
+/// struct Account { balance: i64 }
+/// ```
+///
+/// Newtypes with `#[redis_transparent]` are read like their single field instead, so they accept the same replies
+/// (e.g. `Value::Int` for a `u64` field). They don't need `Deserialize`, and can't use the other attributes.
+///
+/// ```rust
+/// use redis_macros::{FromRedisValue};
+///
+/// #[derive(FromRedisValue)]
+/// #[redis_transparent]
+/// struct UserId(u64);
+/// ```
+///
+/// Enums without fields can be read from their variant name with `#[redis_enum(as_str)]` (following `#[serde(rename)]`
+/// and `#[serde(rename_all)]`), or from their discriminant with `#[redis_enum(repr = u8)]` (or any other integer type).
+/// Unknown values fail with `redis_macros::DeserializeErrorKind::UnknownVariant`.
+///
+/// ```rust
+/// use redis_macros::{FromRedisValue};
+///
+/// #[derive(FromRedisValue)]
+/// #[redis_enum(as_str)]
+/// enum Status { Active, Inactive }
+/// ```
+///
+/// Fixed-shape array replies (e.g. `ZPOPMIN` or the summary of `XPENDING`) can be read with `#[redis_reply(tuple)]`.
+/// The elements are converted to the fields in order, with their own `FromRedisValue`, and the number of elements
+/// must match the number of fields. The errors name the failing field.
+///
+/// ```rust
+/// use redis_macros::{FromRedisValue};
+///
+/// #[derive(FromRedisValue)]
+/// #[redis_reply(tuple)]
+/// struct Scored { member: String, score: f64 }
+/// ```
+///
+/// The generated impl requires `T: DeserializeOwned` for every type parameter `T`. Like `#[serde(bound)]`, you can
+/// replace these bounds with `#[redis_bound(deserialize = "...")]`, e.g. for `PhantomData<T>` markers or associated types.
+///
+/// ```rust
+/// use redis_macros::{FromRedisValue};
+/// use serde::{Deserialize};
+/// use std::marker::PhantomData;
+///
+/// #[derive(FromRedisValue, Deserialize)]
+/// #[redis_bound(deserialize = "")]
+/// struct Id<T> { id: u32, #[serde(skip)] marker: PhantomData<T> }
+/// ```
+///
+/// The generated code uses the `redis` and `serde_json` crates reexported by `redis_macros`, so they don't have to be
+/// in scope. If you use the traits of a crate reexporting `redis` (e.g. `deadpool_redis::redis`), set its path with
+/// `#[redis_crate = "..."]`. Similarly, `#[serde_json_crate = "..."]` uses the given `serde_json` crate instead of the JSON engine.
+/// With `#[redis_crate]`, only the `FromRedisValue` trait of that crate is implemented, without `redis_macros::FromRedisValueWithFormat`.
+/// The crate must reexport the same version of `redis` as `redis_macros`, other versions fail to compile.
+///
+/// The generated code also uses the types and helpers of `redis_macros`. Like serde's `#[serde(crate = "...")]`, set
+/// `#[redis_macros_crate = "..."]` if it is renamed in your dependencies or reexported by another crate.
+///
+/// ```rust
+/// use redis_macros::{FromRedisValue};
+/// use serde::{Deserialize};
+///
+/// #[derive(FromRedisValue, Deserialize)]
+/// #[redis_crate = "redis_macros::__private::redis"]
+/// struct User { id: u32 }
+/// ```
+///
 /// For more information see the isomorphic pair of this trait: [ToRedisArgs].
-#[proc_macro_derive(FromRedisValue, attributes(redis_serializer))]
+#[proc_macro_derive(
+    FromRedisValue,
+    attributes(
+        redis_serializer,
+        redis_redact,
+        redis_errors,
+        redis_validate,
+        redis_envelope,
+        redis_compress,
+        redis_json,
+        redis_transparent,
+        redis_enum,
+        redis_reply,
+        redis_bound,
+        redis_crate,
+        redis_macros_crate,
+        serde_json_crate
+    )
+)]
 pub fn from_redis_value_macro(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_redis_value(input).into()
+}
+
+fn expand_from_redis_value(input: DeriveInput) -> TokenStream2 {
     let DeriveInput {
         ident,
         attrs,
         generics,
+        data,
         ..
-    } = parse_macro_input!(input as DeriveInput);
-    let serializer = get_serializer(attrs, "serde_json");
+    } = input;
+    if let Err(e) = forbid_union(&data, "FromRedisValue") {
+        return e.to_compile_error();
+    }
+    match get_transparent(&attrs) {
+        Ok(Some(attr)) => {
+            return transparent::expand_from_redis_value(&ident, &generics, &attrs, attr, &data)
+                .unwrap_or_else(syn::Error::into_compile_error)
+        }
+        Ok(None) => {}
+        Err(e) => return e.to_compile_error(),
+    }
+    match get_enum_encoding(&attrs) {
+        Ok(Some(encoding)) => {
+            return scalar_enum::expand_from_redis_value(&ident, &generics, &attrs, encoding, &data)
+                .unwrap_or_else(syn::Error::into_compile_error)
+        }
+        Ok(None) => {}
+        Err(e) => return e.to_compile_error(),
+    }
+    match get_reply_tuple(&attrs) {
+        Ok(Some(attr)) => {
+            return tuple_reply::expand(&ident, &generics, &attrs, attr, &data)
+                .unwrap_or_else(syn::Error::into_compile_error)
+        }
+        Ok(None) => {}
+        Err(e) => return e.to_compile_error(),
+    }
+    let serializers = match get_serializers(&attrs, "serde_json") {
+        Ok(serializers) => serializers,
+        Err(e) => return e.to_compile_error(),
+    };
+    let redaction = match get_redaction(&attrs, &data) {
+        Ok(redaction) => redaction,
+        Err(e) => return e.to_compile_error(),
+    };
+    let validators = match get_validators(&attrs) {
+        Ok(validators) => validators,
+        Err(e) => return e.to_compile_error(),
+    };
+    let redis = match get_redis_crate(&attrs) {
+        Ok(redis) => redis,
+        Err(e) => return e.to_compile_error(),
+    };
+    let redis_macros = match get_redis_macros_crate(&attrs) {
+        Ok(redis_macros) => redis_macros,
+        Err(e) => return e.to_compile_error(),
+    };
+    let into_value = match redis_macros_value(&attrs) {
+        Ok(into_value) => into_value,
+        Err(e) => return e.to_compile_error(),
+    };
+    let default_redis = match has_default_redis_crate(&attrs) {
+        Ok(default_redis) => default_redis,
+        Err(e) => return e.to_compile_error(),
+    };
+    let serde_json_crate = match get_serde_json_crate(&attrs) {
+        Ok(serde_json_crate) => serde_json_crate,
+        Err(e) => return e.to_compile_error(),
+    };
+    let brackets = match get_json_brackets(&attrs) {
+        Ok((_, Some(attr)))
+            if !std::iter::once(&serializers.primary)
+                .chain(&serializers.fallbacks)
+                .any(|serializer| serializer.to_string() == "serde_json") =>
+        {
+            return syn::Error::new_spanned(attr, "redis_json is only supported with serde_json")
+                .to_compile_error();
+        }
+        Ok((brackets, _)) => brackets,
+        Err(e) => return e.to_compile_error(),
+    };
     let ident_str = format!("{}", ident);
-    let serializer_str = format!("{}", serializer);
+    let is_named_struct =
+        matches!(&data, syn::Data::Struct(data) if matches!(data.fields, syn::Fields::Named(_)));
+    // Paths like `redis_macros::info` are printed with spaces around the `::`
+    let serializer_str = serializers.primary.to_string().replace(' ', "");
 
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
 
-    let mut where_clause_extended = where_clause.cloned();
+    // Add serde constraints for each type parameter, unless they are set with `#[redis_bound]`
+    let bounds = match get_bounds(&attrs, Direction::Deserialize) {
+        Ok(bounds) => bounds,
+        Err(e) => return e.to_compile_error(),
+    };
+    let defaults = type_param_bounds(&generics, quote! { ::serde::de::DeserializeOwned });
+    let where_clause_extended = where_clause(&generics, bounds, defaults);
 
-    // Add serde constraints for each type parameter
-    for param in &generics.params {
-        if let GenericParam::Type(type_param) = param {
-            let ident = &type_param.ident;
-            let constraint = syn::parse_quote! { #ident : ::serde::de::DeserializeOwned };
+    let where_with_serialize = quote! { #where_clause_extended };
 
-            if let Some(ref mut w) = where_clause_extended {
-                w.predicates.push(constraint);
-            } else {
-                where_clause_extended = Some(syn::parse_quote! { where #constraint });
-            }
+    // serde_json is swapped for the JSON engine selected by the features of redis_macros,
+    // unless the serde_json crate is set with `#[serde_json_crate]`. The errors name serde_json with every engine.
+    let json_from_str = match &serde_json_crate {
+        Some(path) => quote! { #path::from_str },
+        None => {
+            quote! { <#redis_macros::DefaultJsonEngine as #redis_macros::JsonEngine>::from_str }
         }
-    }
+    };
+    let error = quote! { #redis_macros::DeserializeError::with_redaction(#ident_str, #serializer_str, kind, &v, #redaction) };
+
+    let attempt = |serializer: &TokenStream2| {
+        let serializer_str = serializer.to_string().replace(' ', "");
+        let function = if serializer_str == "serde_json" {
+            json_from_str.clone()
+        } else {
+            quote! { #serializer::from_str }
+        };
+        let from_str = quote! { #redis_macros::__private::from_str(#ident_str, #serializer_str, #redaction, &v, s, |s| #function(s)) };
+
+        let from_redis_json = quote! { #redis_macros::__private::from_redis_json_with(#ident_str, #serializer_str, #redaction, &v, s, |s| #json_from_str(s)) };
+        let is_bracketed = quote! { s.starts_with('[') && s.ends_with(']') };
 
-    let where_with_serialize = where_clause_extended
-        .as_ref()
-        .map(|w| quote! { #w })
-        .unwrap_or(quote! {});
+        // The RedisJSON bracket handling is only relevant if we are using serde_json
+        let parse = match brackets {
+            _ if serializer_str != "serde_json" => from_str,
+            // If the parsing failed, the issue might simply be that the user is using a RedisJSON command
+            // RedisJSON commands wrap the response into square brackets for some godforsaken reason
+            // We can try removing the brackets and try the parse again
+            // If the retry fails too, its error is only reported for RedisJSON responses (a single value in the brackets)
+            Brackets::Auto => quote! {
+                #from_str.or_else(|e| {
+                    let mut ch = s.chars();
+                    if ch.next() == ::std::option::Option::Some('[') && ch.next_back() == ::std::option::Option::Some(']') {
+                        let s = ch.as_str();
+                        let is_redis_json = #json_from_str::<::serde::de::IgnoredAny>(s).is_ok();
+                        #from_str.map_err(|retry| if is_redis_json { retry } else { e })
+                    } else {
+                        ::std::result::Result::Err(e)
+                    }
+                })
+            },
+            Brackets::Strict => quote! {
+                if #is_bracketed { #from_redis_json } else { #from_str }
+            },
+            Brackets::Required => quote! {
+                if #is_bracketed {
+                    #from_redis_json
+                } else {
+                    ::std::result::Result::Err(error(#redis_macros::DeserializeErrorKind::NotRedisJson))
+                }
+            },
+            // serde also reads structs from sequences, but the stored structs are always objects
+            Brackets::Off if is_named_struct => quote! {
+                if s.trim_start().starts_with('[') {
+                    let message = ::std::format!("invalid type: sequence, expected struct {}", #ident_str);
+                    ::std::result::Result::Err(error(#redis_macros::DeserializeErrorKind::serde(message, ::std::option::Option::None)))
+                } else {
+                    #from_str
+                }
+            },
+            Brackets::Off => from_str,
+        };
 
-    let failed_parse_error = quote! {
-        ::std::result::Result::Err(::std::format!("Response type not deserializable to {} with {}. (response was {:?})", #ident_str, #serializer_str, v).into())
+        quote! { #parse.map(|parsed| (parsed, #serializer_str)) }
     };
 
-    // If the parsing failed, the issue might simply be that the user is using a RedisJSON command
-    // RedisJSON commands wrap the response into square brackets for some godforsaken reason
-    // We can try removing the brackets and try the parse again
-    let redis_json_hack = quote! {
-        let mut ch = s.chars();
-        if ch.next() == ::std::option::Option::Some('[') && ch.next_back() == ::std::option::Option::Some(']') {
-            if let ::std::result::Result::Ok(s) = #serializer::from_str(ch.as_str()) {
-                ::std::result::Result::Ok(s)
-            } else {
-                ::std::result::Result::Err(::std::format!("Response type not RedisJSON deserializable to {}. (response was {:?})", #ident_str, v).into())
-            }
-        } else {
-            #failed_parse_error
+    // Try the fallback serializers in order, but report the error of the primary serializer
+    let mut parse = attempt(&serializers.primary);
+    for fallback in &serializers.fallbacks {
+        let next = attempt(fallback);
+        parse = quote! { #parse.or_else(|e| #next.map_err(|_| e)) };
+    }
+
+    // Values with envelopes are only deserialized with the serializer of the format id
+    let (format_ids, enveloped): (Vec<_>, Vec<_>) = std::iter::once(&serializers.primary)
+        .chain(&serializers.fallbacks)
+        .filter_map(|serializer| {
+            get_format_id(serializer, &redis_macros).map(|id| (id, attempt(serializer)))
+        })
+        .unzip();
+
+    // Run the validators after deserializing the value successfully
+    let validate = if validators.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            let result = result.and_then(|(parsed, format): (Self, &'static str)| {
+                #(
+                    if let ::std::result::Result::Err(e) = #validators(&parsed) {
+                        return ::std::result::Result::Err(error(#redis_macros::DeserializeErrorKind::Validation(::std::string::ToString::to_string(&e))));
+                    }
+                )*
+                ::std::result::Result::Ok((parsed, format))
+            });
         }
     };
 
-    // The Redis JSON hack only relevant if we are using serde_json
-    let failed_parse = if serializer_str == "serde_json" {
-        redis_json_hack
-    } else {
-        failed_parse_error
+    // The errors are converted to the ParsingError of the redis crate of the path
+    let body = quote! {
+        let error = |kind| #error;
+        let parsing_error = |e: #redis_macros::DeserializeError| #redis::ParsingError::from(::std::string::ToString::to_string(&e));
+        // Text replies like INFO are verbatim strings with RESP3
+        let bytes = match v {
+            #redis_macros::__private::redis::Value::BulkString(ref bytes) => bytes.as_slice(),
+            #redis_macros::__private::redis::Value::VerbatimString { ref text, .. } => text.as_bytes(),
+            _ => return ::std::result::Result::Err(parsing_error(error(#redis_macros::DeserializeErrorKind::UnexpectedValue))),
+        };
+        let (format_id, payload) = match #redis_macros::__private::open_envelope(bytes) {
+            ::std::result::Result::Ok(opened) => opened,
+            ::std::result::Result::Err(kind) => return ::std::result::Result::Err(parsing_error(error(kind))),
+        };
+        let s = match ::std::str::from_utf8(&payload) {
+            ::std::result::Result::Ok(s) => s,
+            ::std::result::Result::Err(e) => {
+                return ::std::result::Result::Err(parsing_error(error(#redis_macros::DeserializeErrorKind::InvalidUtf8(e))));
+            }
+        };
+        let result = match format_id {
+            ::std::option::Option::None => #parse,
+            #(
+                ::std::option::Option::Some(id) if id == #format_ids => #enveloped,
+            )*
+            ::std::option::Option::Some(id) => ::std::result::Result::Err(error(
+                #redis_macros::DeserializeErrorKind::Envelope(::std::format!("unsupported format id {}", id))
+            )),
+        };
+        #validate
+        result.map_err(parsing_error)
     };
 
-    quote! {
-        impl #impl_generics redis::FromRedisValue for #ident #ty_generics #where_with_serialize {
-            fn from_redis_value(v: redis::Value) -> ::std::result::Result<Self, redis::ParsingError> {
-                match v {
-                    redis::Value::BulkString(ref bytes) => {
-                        if let ::std::result::Result::Ok(s) = ::std::str::from_utf8(bytes) {
-                            if let ::std::result::Result::Ok(s) = #serializer::from_str(s) {
-                                ::std::result::Result::Ok(s)
-                            } else {
-                                #failed_parse
-                            }
-                        } else {
-                            ::std::result::Result::Err(::std::format!("Response was not valid UTF-8 string. (response was {:?})", v).into())
-                        }
-                    },
-                    _ => ::std::result::Result::Err(::std::format!("Response type was not deserializable to {}. (response was {:?})", #ident_str, v).into()),
+    // FromRedisValueWithFormat is only implemented for the redis crate of redis_macros
+    if default_redis {
+        quote! {
+            impl #impl_generics #redis_macros::FromRedisValueWithFormat for #ident #ty_generics #where_with_serialize {
+                fn from_redis_value_with_format(v: #redis::Value) -> ::std::result::Result<(Self, &'static str), #redis::ParsingError> {
+                    #body
+                }
+            }
+
+            impl #impl_generics #redis::FromRedisValue for #ident #ty_generics #where_with_serialize {
+                fn from_redis_value(v: #redis::Value) -> ::std::result::Result<Self, #redis::ParsingError> {
+                    <Self as #redis_macros::FromRedisValueWithFormat>::from_redis_value_with_format(v).map(|(parsed, _)| parsed)
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics #redis::FromRedisValue for #ident #ty_generics #where_with_serialize {
+                fn from_redis_value(v: #redis::Value) -> ::std::result::Result<Self, #redis::ParsingError> {
+                    #into_value
+                    let result: ::std::result::Result<(Self, &'static str), #redis::ParsingError> = { #body };
+                    result.map(|(parsed, _)| parsed)
                 }
             }
         }
     }
-    .into()
 }
 
 /// Derive macro for the redis crate's [`ToRedisArgs`](../redis/trait.ToRedisArgs.html) trait to allow passing the type to Redis commands.
@@ -180,52 +513,383 @@ pub fn from_redis_value_macro(input: TokenStream) -> TokenStream {
 /// struct User { id: u32 }
 /// ```
 ///
+/// With the `#[redis_validate(path::to::fn)]` attribute, it also implements `redis_macros::ValidateArgs`,
+/// so you can validate the value with `value.validated()?` before passing it to Redis commands.
+///
+/// To make values self-describing, add `#[redis_envelope]` (or `#[redis_envelope(schema = 1)]`). This writes a small header
+/// with the format id before the value, so `FromRedisValue` can pick the matching serializer (see `redis_macros::Envelope`).
+/// With the `lz4` feature, `#[redis_compress]` also compresses the payload with LZ4, which `FromRedisValue` detects automatically.
+///
+/// `#[redis_key = "user:{id}"]` generates a `redis_key(&self) -> String` method for structs with named fields,
+/// which formats the fields into the template like `format!` (e.g. `{id:04}`, and `{{` to escape the braces).
+///
+/// Newtypes with `#[redis_transparent]` are written like their single field instead of JSON, so a `struct UserId(u64)`
+/// is written as `123` and can be used with numeric commands like `INCR`. They don't need `Serialize`.
+///
+/// Enums without fields can be written as their variant name, without the JSON quotes, with `#[redis_enum(as_str)]`,
+/// or as their discriminant with `#[redis_enum(repr = u8)]` (or any other integer type).
+///
+/// The generated impl requires `T: Serialize` for every type parameter `T`, use `#[redis_bound(serialize = "...")]`
+/// to replace these bounds.
+///
+/// Like `FromRedisValue`, it implements the traits of the `redis` crate reexported by `redis_macros`, which can be changed
+/// with `#[redis_crate = "..."]`, and `#[serde_json_crate = "..."]` replaces the JSON engine.
+///
 /// For more information see the isomorphic pair of this trait: [FromRedisValue].
-#[proc_macro_derive(ToRedisArgs, attributes(redis_serializer))]
+#[proc_macro_derive(
+    ToRedisArgs,
+    attributes(
+        redis_serializer,
+        redis_redact,
+        redis_errors,
+        redis_validate,
+        redis_envelope,
+        redis_compress,
+        redis_key,
+        redis_json,
+        redis_transparent,
+        redis_enum,
+        redis_bound,
+        redis_crate,
+        redis_macros_crate,
+        serde_json_crate
+    )
+)]
 pub fn to_redis_args_macro(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_to_redis_args(input).into()
+}
+
+fn expand_to_redis_args(input: DeriveInput) -> TokenStream2 {
+    let key = redis_key::expand(&input.ident, &input.generics, &input.attrs, &input.data)
+        .unwrap_or_else(syn::Error::into_compile_error);
+    let to_redis_args = expand_to_redis_args_impl(input);
+    quote! {
+        #to_redis_args
+        #key
+    }
+}
+
+fn expand_to_redis_args_impl(input: DeriveInput) -> TokenStream2 {
     let DeriveInput {
         ident,
         attrs,
         generics,
+        data,
         ..
-    } = parse_macro_input!(input as DeriveInput);
-    let serializer = get_serializer(attrs, "serde_json");
+    } = input;
+    if let Err(e) = forbid_union(&data, "ToRedisArgs") {
+        return e.to_compile_error();
+    }
+    match get_transparent(&attrs) {
+        Ok(Some(attr)) => {
+            return transparent::expand_to_redis_args(&ident, &generics, &attrs, attr, &data)
+                .unwrap_or_else(syn::Error::into_compile_error)
+        }
+        Ok(None) => {}
+        Err(e) => return e.to_compile_error(),
+    }
+    match get_enum_encoding(&attrs) {
+        Ok(Some(encoding)) => {
+            return scalar_enum::expand_to_redis_args(&ident, &generics, &attrs, encoding, &data)
+                .unwrap_or_else(syn::Error::into_compile_error)
+        }
+        Ok(None) => {}
+        Err(e) => return e.to_compile_error(),
+    }
+    let serializer = match get_serializer(&attrs, "serde_json") {
+        Ok(serializer) => serializer,
+        Err(e) => return e.to_compile_error(),
+    };
+    // The options of FromRedisValue are not used here, but they are still validated
+    if let Err(e) = get_redaction(&attrs, &data).and(get_json_brackets(&attrs).map(|_| ())) {
+        return e.to_compile_error();
+    }
+    let validators = match get_validators(&attrs) {
+        Ok(validators) => validators,
+        Err(e) => return e.to_compile_error(),
+    };
+    let redis = match get_redis_crate(&attrs) {
+        Ok(redis) => redis,
+        Err(e) => return e.to_compile_error(),
+    };
+    let redis_macros = match get_redis_macros_crate(&attrs) {
+        Ok(redis_macros) => redis_macros,
+        Err(e) => return e.to_compile_error(),
+    };
+    let serde_json_crate = match get_serde_json_crate(&attrs) {
+        Ok(serde_json_crate) => serde_json_crate,
+        Err(e) => return e.to_compile_error(),
+    };
+    let envelope = match get_envelope(&attrs) {
+        Ok(envelope) => envelope,
+        Err(e) => return e.to_compile_error(),
+    };
+    let compress = match get_compress(&attrs) {
+        Ok(compress) => compress.map(|attr| attr.path().span()),
+        Err(e) => return e.to_compile_error(),
+    };
+    let ident_str = format!("{}", ident);
 
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
 
-    let mut where_clause_extended = where_clause.cloned();
+    // Add serde constraints for each type parameter, unless they are set with `#[redis_bound]`
+    let bounds = match get_bounds(&attrs, Direction::Serialize) {
+        Ok(bounds) => bounds,
+        Err(e) => return e.to_compile_error(),
+    };
+    let defaults = type_param_bounds(&generics, quote! { ::serde::Serialize });
+    let where_clause_extended = where_clause(&generics, bounds, defaults);
 
-    // Add serde constraints for each type parameter
-    for param in &generics.params {
-        if let GenericParam::Type(type_param) = param {
-            let ident = &type_param.ident;
-            let constraint = syn::parse_quote! { #ident : ::serde::Serialize };
+    let where_with_serialize = quote! { #where_clause_extended };
 
-            if let Some(ref mut w) = where_clause_extended {
-                w.predicates.push(constraint);
-            } else {
-                where_clause_extended = Some(syn::parse_quote! { where #constraint });
+    // Allow validating the value before writing, if there are validators
+    let validate_args = if validators.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            impl #impl_generics #redis_macros::ValidateArgs for #ident #ty_generics #where_with_serialize {
+                fn validate_args(&self) -> ::std::result::Result<(), #redis_macros::ValidationError> {
+                    #(
+                        #validators(self).map_err(|e| #redis_macros::ValidationError::new(#ident_str, e))?;
+                    )*
+                    ::std::result::Result::Ok(())
+                }
             }
         }
-    }
+    };
+
+    // serde_json is swapped for the JSON engine selected by the features of redis_macros,
+    // unless the serde_json crate is set with `#[serde_json_crate]`
+    let to_string = match serde_json_crate {
+        Some(path) if serializer.to_string() == "serde_json" => quote! { #path::to_string },
+        None if serializer.to_string() == "serde_json" => {
+            quote! { <#redis_macros::DefaultJsonEngine as #redis_macros::JsonEngine>::to_string }
+        }
+        _ => quote! { #serializer::to_string },
+    };
 
-    let where_with_serialize = where_clause_extended
-        .as_ref()
-        .map(|w| quote! { #w })
-        .unwrap_or(quote! {});
+    // Prepend the envelope header to make the value self-describing, compressed values always have an envelope
+    let write = match (
+        envelope,
+        compress,
+        get_format_id(&serializer, &redis_macros),
+    ) {
+        (None, None, _) => quote! { out.write_arg(&buf.as_bytes()) },
+        (Some(schema), None, Some(format_id)) => quote! {
+            let envelope = #redis_macros::Envelope::new(#format_id).with_schema(#schema);
+            out.write_arg(&envelope.write(buf.as_bytes()))
+        },
+        (schema, Some(span), Some(format_id)) => {
+            let schema = schema.map_or_else(|| quote! { 0 }, |schema| quote! { #schema });
+            // The error of the missing lz4 feature points to the attribute
+            let compress: TokenStream2 = quote! {
+                <#redis_macros::__private::Lz4 as #redis_macros::__private::Compress>::compress
+            }
+            .into_iter()
+            .map(|mut token| {
+                token.set_span(span);
+                token
+            })
+            .collect();
+            quote! {
+                let payload = #compress(buf.as_bytes());
+                let envelope = #redis_macros::Envelope::new(#format_id)
+                    .with_flags(#redis_macros::Envelope::FLAG_COMPRESSED)
+                    .with_schema(#schema);
+                out.write_arg(&envelope.write(&payload))
+            }
+        }
+        (_, compress, None) => {
+            let name = if compress.is_some() {
+                "redis_compress"
+            } else {
+                "redis_envelope"
+            };
+            return syn::Error::new_spanned(
+                &serializer,
+                format!("{name} only supports serde_json, serde_yaml, toml and ron serializers"),
+            )
+            .to_compile_error();
+        }
+    };
 
     quote! {
-        impl #impl_generics redis::ToRedisArgs for #ident #ty_generics #where_with_serialize {
+        impl #impl_generics #redis::ToRedisArgs for #ident #ty_generics #where_with_serialize {
             fn write_redis_args<W>(&self, out: &mut W)
             where
-                W: ?::std::marker::Sized + redis::RedisWrite,
+                W: ?::std::marker::Sized + #redis::RedisWrite,
             {
-                let buf = #serializer::to_string(&self).unwrap();
-                out.write_arg(&buf.as_bytes())
+                let buf = #to_string(&self).unwrap();
+                #write
             }
         }
 
-        impl #impl_generics redis::ToSingleRedisArg for #ident #ty_generics #where_with_serialize {}
+        impl #impl_generics #redis::ToSingleRedisArg for #ident #ty_generics #where_with_serialize {}
+
+        #validate_args
+    }
+}
+
+/// Derive macro for both [FromRedisValue] and [ToRedisArgs], configured with a single `#[redis(...)]` attribute.
+///
+/// *NOTE: This requires serde's [`Serialize`](../serde/trait.Serialize.html) and [`Deserialize`](../serde/trait.Deserialize.html)
+/// to also be derived (or implemented).*
+///
+/// The options are the same as the attributes of the separate derives, without the `redis_` prefix:
+///
+/// - `format = serde_yaml` and `fallback(serde_json)`: the serializer and the fallback serializers (`#[redis_serializer]`),
+/// - `envelope` or `envelope(schema = 1)`: write values with the format id and schema id (`#[redis_envelope]`),
+/// - `compress`: compress the values with LZ4 in the envelope, with the `lz4` feature (`#[redis_compress]`),
+/// - `key = "user:{id}"`: generate the `redis_key()` method from the template (`#[redis_key]`),
+/// - `json(brackets = "...")`: the RedisJSON bracket handling (`#[redis_json]`),
+/// - `errors(redact_payload)`: hide the response in the errors (`#[redis_errors]`), or `#[redis(redact)]` on the fields,
+/// - `validate(path::to::fn)`: validate the value after reading and before writing (`#[redis_validate]`),
+/// - `bound(deserialize = "...", serialize = "...")`: replace the generated bounds (`#[redis_bound]`),
+/// - `transparent` and `enum(as_str)` or `enum(repr = u8)`: read and write newtypes and fieldless enums as plain values,
+/// - `crate = "..."`, `redis_macros_crate = "..."` and `serde_json_crate = "..."`: the paths of the `redis`,
+///   `redis_macros` and `serde_json` crates.
+///
+/// ```rust
+/// use redis_macros::RedisValue;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, RedisValue)]
+/// #[redis(format = serde_yaml, fallback(serde_json), envelope(schema = 2))]
+/// struct User {
+///     id: u32,
+///     #[redis(redact)]
+///     password: String,
+/// }
+/// ```
+///
+/// `#[redis_reply(tuple)]` types can only be read, so they derive [FromRedisValue] instead.
+///
+/// With the `lz4` feature, `compress` (`#[redis_compress]`) also compresses the payload of the envelope. The `key = "..."`
+/// template (`#[redis_key = "..."]`) generates a `redis_key()` method, formatting the fields with `format!`:
+///
+/// ```rust
+/// use redis_macros::RedisValue;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, RedisValue)]
+/// #[redis(key = "session:{user_id}:{id}")]
+/// struct Session {
+///     id: u32,
+///     user_id: u64,
+/// }
+///
+/// assert_eq!(Session { id: 7, user_id: 3 }.redis_key(), "session:3:7");
+/// ```
+#[proc_macro_derive(RedisValue, attributes(redis))]
+pub fn redis_value_macro(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as DeriveInput);
+    if let Err(e) = redis_value::translate(&mut input.attrs, &mut input.data) {
+        return e.to_compile_error().into();
+    }
+    let from_redis_value = expand_from_redis_value(input.clone());
+    let to_redis_args = expand_to_redis_args(input);
+    quote! {
+        #from_redis_value
+        #to_redis_args
     }
     .into()
 }
+
+/// Derive macro to get only some JSONPaths of a RedisJSON document with a single `JSON.GET` command.
+///
+/// Each field needs a `#[json_path("...")]` attribute with the JSONPath to get the field from. The derive generates the
+/// `JSON_PATHS` constant to pass to `JSON.GET`, and implements [`FromRedisValue`](../redis/trait.FromRedisValue.html)
+/// for the reply, which is an object of the matches keyed by the paths. Every path must match exactly one value.
+///
+/// *NOTE: The field types must implement serde's [`Deserialize`](../serde/trait.Deserialize.html), the struct itself doesn't.*
+///
+/// ```rust,no_run
+/// # use redis::{Client, JsonCommands, RedisResult};
+/// use redis_macros::RedisJsonProjection;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Address { street: String }
+///
+/// #[derive(RedisJsonProjection)]
+/// struct UserSummary {
+///     #[json_path("$.name")]
+///     name: String,
+///     #[json_path("$.addresses[0]")]
+///     address: Address,
+/// }
+///
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// let summary: UserSummary = con.json_get("user", UserSummary::JSON_PATHS)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Like `FromRedisValue`, the reply is parsed with the JSON engine selected by the features (or the crate of
+/// `#[serde_json_crate = "..."]`), and `#[redis_redact]` on the fields or `#[redis_errors(redact_payload)]` keep
+/// the matches out of the errors.
+#[proc_macro_derive(
+    RedisJsonProjection,
+    attributes(
+        json_path,
+        redis_bound,
+        redis_crate,
+        redis_macros_crate,
+        redis_errors,
+        redis_redact,
+        serde_json_crate
+    )
+)]
+pub fn redis_json_projection_macro(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    projection::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive macro to generate typed JSONPaths from the fields of a struct.
+///
+/// It generates a `{Name}Path` type with an accessor for each field, starting from `Name::path()` (the root of the document).
+/// The accessors return the path types of the fields, so nested structs (deriving `RedisJsonPath` too), arrays
+/// (with `index`) and maps (with `key`) can be followed further. The names follow the serialized names of serde,
+/// honouring `#[serde(rename)]`, `#[serde(rename_all)]`, `#[serde(flatten)]` and `#[serde(skip)]`.
+///
+/// The field types must implement `redis_macros::HasJsonPath`: for other types, add `#[redis_path(leaf)]` to the field.
+/// On enums, it only implements `HasJsonPath`, so they can be used as fields.
+///
+/// The accessors require `T: HasJsonPath` for every type parameter `T`, use `#[redis_bound(serialize = "...")]`
+/// to replace these bounds.
+///
+/// ```rust
+/// use redis_macros::RedisJsonPath;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, RedisJsonPath)]
+/// enum Address {
+///     Street(String),
+///     Road(String),
+/// }
+///
+/// #[derive(Serialize, Deserialize, RedisJsonPath)]
+/// #[serde(rename_all = "camelCase")]
+/// struct User {
+///     user_name: String,
+///     addresses: Vec<Address>,
+/// }
+///
+/// assert_eq!(User::path().user_name().as_str(), "$.userName");
+/// assert_eq!(User::path().addresses().index(0).as_str(), "$.addresses[0]");
+/// ```
+#[proc_macro_derive(
+    RedisJsonPath,
+    attributes(redis_path, redis_bound, redis_crate, redis_macros_crate)
+)]
+pub fn redis_json_path_macro(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    json_path::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,92 @@
+//! Generate the code of `#[derive(RedisJsonProjection)]`.
+
+use crate::attrs::{
+    get_bounds, get_json_path, get_redis_crate, get_redis_macros_crate, get_serde_json_crate,
+    is_redacted, redacts_payload, redis_macros_value, type_param_bounds, where_clause,
+};
+use crate::serde_names::Direction;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DataStruct, DeriveInput, Fields};
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let DeriveInput {
+        ident,
+        attrs,
+        generics,
+        data,
+        ..
+    } = input;
+    let Data::Struct(DataStruct {
+        fields: Fields::Named(fields),
+        ..
+    }) = data
+    else {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "RedisJsonProjection is only supported on structs with named fields",
+        ));
+    };
+    let names = fields.named.iter().map(|field| &field.ident);
+    let paths = fields
+        .named
+        .iter()
+        .map(get_json_path)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let ident_str = format!("{}", ident);
+    let redis = get_redis_crate(&attrs)?;
+    let redis_macros = get_redis_macros_crate(&attrs)?;
+    let into_value = redis_macros_value(&attrs)?;
+
+    // The fields are named by their paths in the response, so the paths of the `#[redis_redact]` fields are hidden
+    let mut redacted = Vec::new();
+    for (field, path) in fields.named.iter().zip(&paths) {
+        if is_redacted(field)? {
+            redacted.push(path);
+        }
+    }
+    let redaction = if redacts_payload(&attrs)? {
+        quote! { #redis_macros::Redaction::Payload }
+    } else if redacted.is_empty() {
+        quote! { #redis_macros::Redaction::None }
+    } else {
+        quote! { #redis_macros::Redaction::Fields(&[#(#redacted),*]) }
+    };
+
+    // The reply is parsed with the JSON engine of redis_macros, unless the serde_json crate is set with `#[serde_json_crate]`
+    let json_from_str = match get_serde_json_crate(&attrs)? {
+        Some(path) => quote! { #path::from_str },
+        None => {
+            quote! { <#redis_macros::DefaultJsonEngine as #redis_macros::JsonEngine>::from_str }
+        }
+    };
+
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+
+    // Add serde constraints for each type parameter, unless they are set with `#[redis_bound]`
+    let bounds = get_bounds(&attrs, Direction::Deserialize)?;
+    let defaults = type_param_bounds(&generics, quote! { ::serde::de::DeserializeOwned });
+    let where_with_serialize = where_clause(&generics, bounds, defaults);
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_with_serialize {
+            /// The JSONPaths of the fields, to pass to `JSON.GET`.
+            pub const JSON_PATHS: &'static [&'static str] = &[#(#paths),*];
+        }
+
+        impl #impl_generics #redis::FromRedisValue for #ident #ty_generics #where_with_serialize {
+            fn from_redis_value(v: #redis::Value) -> ::std::result::Result<Self, #redis::ParsingError> {
+                #into_value
+                // The errors are converted to the ParsingError of the redis crate of the path
+                let parsing_error = |e: #redis_macros::DeserializeError| #redis::ParsingError::from(::std::string::ToString::to_string(&e));
+                let projection = #redis_macros::__private::json_projection(#ident_str, &v, Self::JSON_PATHS, #redaction, |s| #json_from_str(s))
+                    .map_err(parsing_error)?;
+                ::std::result::Result::Ok(Self {
+                    #(
+                        #names: projection.get(#paths).map_err(parsing_error)?,
+                    )*
+                })
+            }
+        }
+    })
+}
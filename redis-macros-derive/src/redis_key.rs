@@ -0,0 +1,109 @@
+//! Generate the `redis_key` method from the `#[redis_key = "..."]` template of the type.
+
+use crate::attrs::get_key_template;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{ext::IdentExt, Attribute, Data, DataStruct, Fields, Generics, Ident, LitStr};
+
+/// Return the field names of the placeholders in the template, e.g. `id` for `user:{id}` or `{id:>5}`.
+fn placeholders(template: &LitStr) -> syn::Result<Vec<String>> {
+    let value = template.value();
+    let mut names: Vec<String> = Vec::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => {
+                            return Err(syn::Error::new_spanned(
+                                template,
+                                "unclosed `{` in the key template, use `{{` to escape it",
+                            ))
+                        }
+                    }
+                }
+                let name = placeholder.split(':').next().unwrap_or_default().trim();
+                if syn::parse_str::<Ident>(name).is_err() {
+                    return Err(syn::Error::new_spanned(
+                        template,
+                        format!("the placeholders of the key template must be field names, found `{{{placeholder}}}`"),
+                    ));
+                }
+                if !names.iter().any(|existing| existing == name) {
+                    names.push(name.to_string());
+                }
+            }
+            '}' => {
+                return Err(syn::Error::new_spanned(
+                    template,
+                    "unmatched `}` in the key template, use `}}` to escape it",
+                ))
+            }
+            _ => {}
+        }
+    }
+    Ok(names)
+}
+
+/// Implement `redis_key(&self) -> String` for the structs with `#[redis_key = "..."]`, formatting the fields into the template.
+pub fn expand(
+    ident: &Ident,
+    generics: &Generics,
+    attrs: &[Attribute],
+    data: &Data,
+) -> syn::Result<TokenStream2> {
+    let Some(template) = get_key_template(attrs)? else {
+        return Ok(quote! {});
+    };
+    let Data::Struct(DataStruct {
+        fields: Fields::Named(fields),
+        ..
+    }) = data
+    else {
+        return Err(syn::Error::new_spanned(
+            &template,
+            "redis_key is only supported on structs with named fields",
+        ));
+    };
+
+    let names = placeholders(&template)?
+        .into_iter()
+        .map(|name| {
+            fields
+                .named
+                .iter()
+                .filter_map(|field| field.ident.as_ref())
+                .find(|field| field.unraw() == name)
+                .ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        &template,
+                        format!("no field `{name}` in `{ident}` for the key template"),
+                    )
+                })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let doc = format!(
+        "Return the Redis key of the value, formatted from `{}`.",
+        template.value()
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #[doc = #doc]
+            pub fn redis_key(&self) -> ::std::string::String {
+                ::std::format!(#template, #(#names = self.#names),*)
+            }
+        }
+    })
+}
@@ -0,0 +1,111 @@
+//! Translate the `#[redis(...)]` options of `#[derive(RedisValue)]` to the attributes of the other derives.
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote_spanned};
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{parenthesized, punctuated::Punctuated, Attribute, Data, Expr, Path, Token};
+
+/// The options of `#[redis(...)]` on the type, with the attributes they stand for.
+const OPTIONS: &[(&str, &str)] = &[
+    ("envelope", "redis_envelope"),
+    ("compress", "redis_compress"),
+    ("key", "redis_key"),
+    ("json", "redis_json"),
+    ("errors", "redis_errors"),
+    ("validate", "redis_validate"),
+    ("bound", "redis_bound"),
+    ("transparent", "redis_transparent"),
+    ("enum", "redis_enum"),
+    ("crate", "redis_crate"),
+    ("redis_macros_crate", "redis_macros_crate"),
+    ("serde_json_crate", "serde_json_crate"),
+];
+
+/// Replace the `#[redis(...)]` attributes of the type and its fields with the attributes of `FromRedisValue` and `ToRedisArgs`.
+pub fn translate(attrs: &mut Vec<Attribute>, data: &mut Data) -> syn::Result<()> {
+    let mut translated = Vec::new();
+    let mut format = None;
+    let mut fallbacks = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("redis")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("format") {
+                if format.is_some() {
+                    return Err(meta.error("duplicate redis format"));
+                }
+                format = Some(meta.value()?.parse::<Path>()?);
+                Ok(())
+            } else if meta.path.is_ident("fallback") {
+                let content;
+                parenthesized!(content in meta.input);
+                fallbacks.extend(Punctuated::<Path, Token![,]>::parse_terminated(&content)?);
+                Ok(())
+            } else if let Some((_, name)) = OPTIONS.iter().find(|(key, _)| meta.path.is_ident(key)) {
+                translated.push(translate_option(&meta, name)?);
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "unsupported redis option, expected one of `format`, `fallback`, `envelope`, `compress`, `key`, \
+                     `json`, `errors`, `validate`, `bound`, `transparent`, `enum`, `crate`, `redis_macros_crate` or `serde_json_crate`",
+                ))
+            }
+        })?;
+    }
+    if format.is_some() || !fallbacks.is_empty() {
+        let span = format.as_ref().map_or_else(Span::call_site, Spanned::span);
+        let format = format.unwrap_or_else(|| syn::parse_quote! { serde_json });
+        let fallback = if fallbacks.is_empty() {
+            quote_spanned! { span=> }
+        } else {
+            quote_spanned! { span=> , fallback(#(#fallbacks),*) }
+        };
+        translated
+            .push(syn::parse_quote_spanned! { span=> #[redis_serializer(#format #fallback)] });
+    }
+    attrs.retain(|attr| !attr.path().is_ident("redis"));
+    attrs.extend(translated);
+
+    let fields: Box<dyn Iterator<Item = &mut syn::Field>> = match data {
+        Data::Struct(data) => Box::new(data.fields.iter_mut()),
+        Data::Enum(data) => Box::new(data.variants.iter_mut().flat_map(|v| v.fields.iter_mut())),
+        Data::Union(data) => Box::new(data.fields.named.iter_mut()),
+    };
+    for field in fields {
+        let mut translated = Vec::new();
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("redis"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("redact") {
+                    translated.push(translate_option(&meta, "redis_redact")?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported redis field option, expected `redact`"))
+                }
+            })?;
+        }
+        field.attrs.retain(|attr| !attr.path().is_ident("redis"));
+        field.attrs.extend(translated);
+    }
+    Ok(())
+}
+
+/// Return the attribute for the option, keeping its arguments (e.g. `envelope(schema = 1)` to `#[redis_envelope(schema = 1)]`).
+fn translate_option(meta: &ParseNestedMeta, name: &str) -> syn::Result<Attribute> {
+    let span = meta.path.span();
+    let name = format_ident!("{}", name, span = span);
+    let arguments: TokenStream2 = if meta.input.peek(Token![=]) {
+        let value = meta.value()?.parse::<Expr>()?;
+        quote_spanned! { span=> = #value }
+    } else if meta.input.peek(syn::token::Paren) {
+        let content;
+        parenthesized!(content in meta.input);
+        let content = content.parse::<TokenStream2>()?;
+        quote_spanned! { span=> (#content) }
+    } else {
+        TokenStream2::new()
+    };
+    Ok(syn::parse_quote_spanned! { span=> #[#name #arguments] })
+}
@@ -0,0 +1,222 @@
+//! Generate the code of `#[derive(FromRedisValue, ToRedisArgs)]` with `#[redis_enum(...)]`.
+
+use crate::attrs::{get_redis_crate, get_redis_macros_crate, redis_macros_value, EnumEncoding};
+use crate::serde_names::{self, Direction};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Attribute, Data, DataEnum, Expr, ExprLit, ExprUnary, Fields, Generics, Ident, Lit, UnOp,
+    Variant,
+};
+
+fn get_variants<'a>(attr: &Attribute, data: &'a Data) -> syn::Result<Vec<&'a Variant>> {
+    let Data::Enum(DataEnum { variants, .. }) = data else {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "redis_enum is only supported on enums",
+        ));
+    };
+    variants
+        .iter()
+        .map(|variant| match variant.fields {
+            Fields::Unit => Ok(variant),
+            _ => Err(syn::Error::new_spanned(
+                variant,
+                "redis_enum is only supported on enums without fields",
+            )),
+        })
+        .collect()
+}
+
+/// The range of the integer type of `#[redis_enum(repr = ...)]`, `usize` and `isize` are checked as 64 bits.
+fn repr_range(repr: &Ident) -> (i128, i128) {
+    match repr.to_string().as_str() {
+        "u8" => (0, u8::MAX.into()),
+        "u16" => (0, u16::MAX.into()),
+        "u32" => (0, u32::MAX.into()),
+        "u64" | "usize" => (0, u64::MAX.into()),
+        "i8" => (i8::MIN.into(), i8::MAX.into()),
+        "i16" => (i16::MIN.into(), i16::MAX.into()),
+        "i32" => (i32::MIN.into(), i32::MAX.into()),
+        _ => (i64::MIN.into(), i64::MAX.into()),
+    }
+}
+
+/// The value of a discriminant written as an integer literal, with an optional minus sign.
+fn literal_discriminant(expr: &Expr) -> Option<i128> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(int), ..
+        }) => int.base10_parse().ok(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => literal_discriminant(expr).map(|value| -value),
+        Expr::Group(group) => literal_discriminant(&group.expr),
+        Expr::Paren(paren) => literal_discriminant(&paren.expr),
+        _ => None,
+    }
+}
+
+/// Check that the discriminants fit the `#[redis_enum(repr = ...)]` type, so they are not truncated by `as`.
+///
+/// The discriminants that are not integer literals can't be evaluated by the derive and are left to the compiler,
+/// as are the implicit discriminants that follow them.
+fn check_discriminants(variants: &[&Variant], repr: &Ident) -> syn::Result<()> {
+    let (min, max) = repr_range(repr);
+    let mut next = Some(0);
+    for variant in variants {
+        let value = match &variant.discriminant {
+            Some((_, expr)) => literal_discriminant(expr),
+            None => next,
+        };
+        if let Some(value) = value {
+            if value < min || value > max {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    format!(
+                        "the discriminant {value} of `{}` doesn't fit in `{repr}`",
+                        variant.ident
+                    ),
+                ));
+            }
+        }
+        next = value.map(|value| value + 1);
+    }
+    Ok(())
+}
+
+/// The names of the variants, honoring `#[serde(rename)]` and `#[serde(rename_all)]`.
+fn variant_names(attrs: &[Attribute], variants: &[&Variant], direction: Direction) -> Vec<String> {
+    let rename_all = serde_names::rename_all_for(attrs, direction);
+    variants
+        .iter()
+        .map(|variant| serde_names::variant_name_for(variant, rename_all.as_deref(), direction))
+        .collect()
+}
+
+pub fn expand_from_redis_value(
+    ident: &Ident,
+    generics: &Generics,
+    attrs: &[Attribute],
+    (encoding, attr): (EnumEncoding, &Attribute),
+    data: &Data,
+) -> syn::Result<TokenStream2> {
+    let variants = get_variants(attr, data)?;
+    let redis = get_redis_crate(attrs)?;
+    let redis_macros = get_redis_macros_crate(attrs)?;
+    let into_value = redis_macros_value(attrs)?;
+    let idents = variants.iter().map(|variant| &variant.ident);
+    let ident_str = format!("{}", ident);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match encoding {
+        EnumEncoding::AsStr => {
+            let names = variant_names(attrs, &variants, Direction::Deserialize);
+            quote! {
+                #into_value
+                let format = "redis_enum(as_str)";
+                let name = #redis_macros::__private::variant_name(#ident_str, format, &v).map_err(parsing_error)?;
+                match name {
+                    #(
+                        #names => ::std::result::Result::Ok(Self::#idents),
+                    )*
+                    _ => {
+                        let kind = #redis_macros::DeserializeErrorKind::unknown_variant(name, [#(#names),*]);
+                        ::std::result::Result::Err(parsing_error(#redis_macros::DeserializeError::new(#ident_str, format, kind, &v)))
+                    }
+                }
+            }
+        }
+        EnumEncoding::Repr(repr) => {
+            check_discriminants(&variants, &repr)?;
+            let format = format!("redis_enum(repr = {repr})");
+            let discriminants = variants
+                .iter()
+                .map(|variant| {
+                    let ident = &variant.ident;
+                    quote! { Self::#ident as #repr }
+                })
+                .collect::<Vec<_>>();
+            quote! {
+                let discriminant = <#repr as #redis::FromRedisValue>::from_redis_value_ref(&v)?;
+                #into_value
+                #(
+                    if discriminant == #discriminants {
+                        return ::std::result::Result::Ok(Self::#idents);
+                    }
+                )*
+                let kind = #redis_macros::DeserializeErrorKind::unknown_variant(discriminant, [#(#discriminants),*]);
+                ::std::result::Result::Err(parsing_error(#redis_macros::DeserializeError::new(#ident_str, #format, kind, &v)))
+            }
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #redis::FromRedisValue for #ident #ty_generics #where_clause {
+            fn from_redis_value(v: #redis::Value) -> ::std::result::Result<Self, #redis::ParsingError> {
+                // The errors are converted to the ParsingError of the redis crate of the path
+                let parsing_error = |e: #redis_macros::DeserializeError| #redis::ParsingError::from(::std::string::ToString::to_string(&e));
+                #body
+            }
+        }
+    })
+}
+
+pub fn expand_to_redis_args(
+    ident: &Ident,
+    generics: &Generics,
+    attrs: &[Attribute],
+    (encoding, attr): (EnumEncoding, &Attribute),
+    data: &Data,
+) -> syn::Result<TokenStream2> {
+    let variants = get_variants(attr, data)?;
+    let redis = get_redis_crate(attrs)?;
+    let idents = variants.iter().map(|variant| &variant.ident);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (write, numeric_behavior) = match encoding {
+        EnumEncoding::AsStr => {
+            let names = variant_names(attrs, &variants, Direction::Serialize);
+            let write = quote! {
+                let name = match self {
+                    #(
+                        Self::#idents => #names,
+                    )*
+                };
+                out.write_arg(name.as_bytes())
+            };
+            (write, quote! { #redis::NumericBehavior::NonNumeric })
+        }
+        EnumEncoding::Repr(repr) => {
+            check_discriminants(&variants, &repr)?;
+            let write = quote! {
+                let discriminant = match self {
+                    #(
+                        Self::#idents => Self::#idents as #repr,
+                    )*
+                };
+                #redis::ToRedisArgs::write_redis_args(&discriminant, out)
+            };
+            (write, quote! { #redis::NumericBehavior::NumberIsInteger })
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #redis::ToRedisArgs for #ident #ty_generics #where_clause {
+            fn write_redis_args<W>(&self, out: &mut W)
+            where
+                W: ?::std::marker::Sized + #redis::RedisWrite,
+            {
+                #write
+            }
+
+            fn describe_numeric_behavior(&self) -> #redis::NumericBehavior {
+                #numeric_behavior
+            }
+        }
+
+        impl #impl_generics #redis::ToSingleRedisArg for #ident #ty_generics #where_clause {}
+    })
+}
@@ -0,0 +1,172 @@
+//! Resolve the names that serde uses for fields, honoring `#[serde(rename)]` and `#[serde(rename_all)]`.
+
+use proc_macro2::TokenStream as TokenStream2;
+use syn::{ext::IdentExt, parenthesized, Attribute, Expr, Field, LitStr, Token, Variant};
+
+/// The names used for deserializing (which appear in the errors) or for serializing (which are stored in Redis).
+#[derive(Clone, Copy)]
+pub enum Direction {
+    Deserialize,
+    Serialize,
+}
+
+impl Direction {
+    pub fn key(self) -> &'static str {
+        match self {
+            Direction::Deserialize => "deserialize",
+            Direction::Serialize => "serialize",
+        }
+    }
+}
+
+/// Parse `rename` or `rename_all` from the serde attributes, either as `key = "..."` or as
+/// `key(deserialize = "...", serialize = "...")`.
+fn serde_attr(attrs: &[Attribute], key: &str, direction: Direction) -> Option<String> {
+    let mut found = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        // Malformed serde attributes are reported by serde itself
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(key) {
+                if meta.input.peek(Token![=]) {
+                    found = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else {
+                    meta.parse_nested_meta(|inner| {
+                        let value = inner.value()?.parse::<LitStr>()?.value();
+                        if inner.path.is_ident(direction.key()) {
+                            found = Some(value);
+                        }
+                        Ok(())
+                    })?;
+                }
+            } else if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let content;
+                parenthesized!(content in meta.input);
+                content.parse::<TokenStream2>()?;
+            }
+            Ok(())
+        });
+    }
+    found
+}
+
+/// Return the container's `rename_all` rule for deserializing, if there is one.
+pub fn rename_all(attrs: &[Attribute]) -> Option<String> {
+    rename_all_for(attrs, Direction::Deserialize)
+}
+
+/// Return the container's `rename_all` rule, if there is one.
+pub fn rename_all_for(attrs: &[Attribute], direction: Direction) -> Option<String> {
+    serde_attr(attrs, "rename_all", direction)
+}
+
+/// Return the name of a named field in the deserialized format.
+pub fn field_name(field: &Field, rename_all: Option<&str>) -> String {
+    field_name_for(field, rename_all, Direction::Deserialize)
+}
+
+/// Return the name of a named field in the serialized or deserialized format.
+pub fn field_name_for(field: &Field, rename_all: Option<&str>, direction: Direction) -> String {
+    if let Some(rename) = serde_attr(&field.attrs, "rename", direction) {
+        return rename;
+    }
+    let name = field
+        .ident
+        .as_ref()
+        .map(|ident| ident.unraw().to_string())
+        .unwrap_or_default();
+    match rename_all {
+        Some(rule) => rename_field(&name, rule),
+        None => name,
+    }
+}
+
+/// Return the name of an enum variant in the serialized or deserialized format.
+pub fn variant_name_for(
+    variant: &Variant,
+    rename_all: Option<&str>,
+    direction: Direction,
+) -> String {
+    if let Some(rename) = serde_attr(&variant.attrs, "rename", direction) {
+        return rename;
+    }
+    let name = variant.ident.unraw().to_string();
+    match rename_all {
+        Some(rule) => rename_variant(&name, rule),
+        None => name,
+    }
+}
+
+/// Apply the `rename_all` rule to a PascalCase variant name, the same way serde does.
+fn rename_variant(name: &str, rule: &str) -> String {
+    let snake = || {
+        let mut snake = String::new();
+        for (i, ch) in name.char_indices() {
+            if i > 0 && ch.is_uppercase() {
+                snake.push('_');
+            }
+            snake.push(ch.to_ascii_lowercase());
+        }
+        snake
+    };
+    match rule {
+        "lowercase" => name.to_ascii_lowercase(),
+        "UPPERCASE" => name.to_ascii_uppercase(),
+        "camelCase" => name[..1].to_ascii_lowercase() + &name[1..],
+        "snake_case" => snake(),
+        "SCREAMING_SNAKE_CASE" => snake().to_ascii_uppercase(),
+        "kebab-case" => snake().replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => snake().replace('_', "-").to_ascii_uppercase(),
+        _ => name.to_string(),
+    }
+}
+
+/// Apply the `rename_all` rule to a snake_case field name, the same way serde does.
+fn rename_field(name: &str, rule: &str) -> String {
+    let pascal = || {
+        name.split('_')
+            .map(|part| {
+                let mut ch = part.chars();
+                ch.next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + ch.as_str())
+                    .unwrap_or_default()
+            })
+            .collect::<String>()
+    };
+    match rule {
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => name.to_ascii_uppercase(),
+        "PascalCase" => pascal(),
+        "camelCase" => {
+            let pascal = pascal();
+            let mut ch = pascal.chars();
+            ch.next()
+                .map(|first| first.to_ascii_lowercase().to_string() + ch.as_str())
+                .unwrap_or_default()
+        }
+        "kebab-case" => name.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => name.replace('_', "-").to_ascii_uppercase(),
+        _ => name.to_string(),
+    }
+}
+
+/// Return true if the serde attributes contain any of the flags (e.g. `#[serde(flatten)]`).
+pub fn has_flag(attrs: &[Attribute], flags: &[&str]) -> bool {
+    let mut found = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            if flags.iter().any(|flag| meta.path.is_ident(flag)) {
+                found = true;
+            }
+            if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let content;
+                parenthesized!(content in meta.input);
+                content.parse::<TokenStream2>()?;
+            }
+            Ok(())
+        });
+    }
+    found
+}
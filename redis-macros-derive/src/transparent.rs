@@ -0,0 +1,112 @@
+//! Generate the code of `#[derive(FromRedisValue, ToRedisArgs)]` with `#[redis_transparent]`.
+
+use crate::attrs::{get_bounds, get_redis_crate, where_clause};
+use crate::serde_names::Direction;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Attribute, Data, DataStruct, Fields, Generics, Ident, Index, Member, Type};
+
+/// The single field of a transparent struct.
+struct Inner<'a> {
+    member: Member,
+    ty: &'a Type,
+}
+
+fn get_inner<'a>(attr: &Attribute, data: &'a Data) -> syn::Result<Inner<'a>> {
+    let error = || {
+        syn::Error::new_spanned(
+            attr,
+            "redis_transparent is only supported on structs with a single field",
+        )
+    };
+    let Data::Struct(DataStruct { fields, .. }) = data else {
+        return Err(error());
+    };
+    let mut iter = fields.iter();
+    let (Some(field), None) = (iter.next(), iter.next()) else {
+        return Err(error());
+    };
+    let member = match (&field.ident, fields) {
+        (Some(ident), _) => Member::Named(ident.clone()),
+        (None, Fields::Unnamed(_)) => Member::Unnamed(Index::from(0)),
+        (None, _) => return Err(error()),
+    };
+    Ok(Inner {
+        member,
+        ty: &field.ty,
+    })
+}
+
+pub fn expand_from_redis_value(
+    ident: &Ident,
+    generics: &Generics,
+    attrs: &[Attribute],
+    attr: &Attribute,
+    data: &Data,
+) -> syn::Result<TokenStream2> {
+    let Inner { member, ty } = get_inner(attr, data)?;
+    let redis = get_redis_crate(attrs)?;
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let bounds = get_bounds(attrs, Direction::Deserialize)?;
+    let where_clause = where_clause(
+        generics,
+        bounds,
+        vec![syn::parse_quote! { #ty: #redis::FromRedisValue }],
+    );
+
+    Ok(quote! {
+        impl #impl_generics #redis::FromRedisValue for #ident #ty_generics #where_clause {
+            fn from_redis_value_ref(v: &#redis::Value) -> ::std::result::Result<Self, #redis::ParsingError> {
+                <#ty as #redis::FromRedisValue>::from_redis_value_ref(v).map(|inner| Self { #member: inner })
+            }
+
+            fn from_redis_value(v: #redis::Value) -> ::std::result::Result<Self, #redis::ParsingError> {
+                <#ty as #redis::FromRedisValue>::from_redis_value(v).map(|inner| Self { #member: inner })
+            }
+        }
+    })
+}
+
+pub fn expand_to_redis_args(
+    ident: &Ident,
+    generics: &Generics,
+    attrs: &[Attribute],
+    attr: &Attribute,
+    data: &Data,
+) -> syn::Result<TokenStream2> {
+    let Inner { member, ty } = get_inner(attr, data)?;
+    let redis = get_redis_crate(attrs)?;
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let bounds = get_bounds(attrs, Direction::Serialize)?;
+    let where_args = where_clause(
+        generics,
+        bounds.clone(),
+        vec![syn::parse_quote! { #ty: #redis::ToRedisArgs }],
+    );
+    let where_single = where_clause(
+        generics,
+        bounds,
+        vec![syn::parse_quote! { #ty: #redis::ToSingleRedisArg }],
+    );
+
+    Ok(quote! {
+        impl #impl_generics #redis::ToRedisArgs for #ident #ty_generics #where_args {
+            fn write_redis_args<W>(&self, out: &mut W)
+            where
+                W: ?::std::marker::Sized + #redis::RedisWrite,
+            {
+                <#ty as #redis::ToRedisArgs>::write_redis_args(&self.#member, out)
+            }
+
+            fn describe_numeric_behavior(&self) -> #redis::NumericBehavior {
+                <#ty as #redis::ToRedisArgs>::describe_numeric_behavior(&self.#member)
+            }
+
+            fn num_of_args(&self) -> usize {
+                <#ty as #redis::ToRedisArgs>::num_of_args(&self.#member)
+            }
+        }
+
+        impl #impl_generics #redis::ToSingleRedisArg for #ident #ty_generics #where_single {}
+    })
+}
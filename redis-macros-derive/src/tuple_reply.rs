@@ -0,0 +1,78 @@
+//! Generate the code of `#[derive(FromRedisValue)]` with `#[redis_reply(tuple)]`.
+
+use crate::attrs::{
+    get_bounds, get_redis_crate, get_redis_macros_crate, redis_macros_value, where_clause,
+};
+use crate::serde_names::Direction;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Attribute, Data, DataStruct, Fields, Generics, Ident, Index, Member};
+
+pub fn expand(
+    ident: &Ident,
+    generics: &Generics,
+    attrs: &[Attribute],
+    attr: &Attribute,
+    data: &Data,
+) -> syn::Result<TokenStream2> {
+    let Data::Struct(DataStruct { fields, .. }) = data else {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "redis_reply(tuple) is only supported on structs",
+        ));
+    };
+    let members = fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        })
+        .collect::<Vec<_>>();
+    let names = members.iter().map(|member| match member {
+        Member::Named(ident) => ident.to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    });
+    let len = members.len();
+    let ident_str = format!("{}", ident);
+    let redis = get_redis_crate(attrs)?;
+    let redis_macros = get_redis_macros_crate(attrs)?;
+    let into_value = redis_macros_value(attrs)?;
+
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+
+    // Add redis constraints for the type of each field, unless they are set with `#[redis_bound]`
+    let bounds = get_bounds(attrs, Direction::Deserialize)?;
+    let defaults = fields
+        .iter()
+        .map(|field| {
+            let ty = &field.ty;
+            syn::parse_quote! { #ty : #redis::FromRedisValue }
+        })
+        .collect();
+    let where_clause_extended = where_clause(generics, bounds, defaults);
+
+    let construct = match fields {
+        Fields::Unit => quote! { Self },
+        _ => quote! {
+            Self {
+                #(
+                    #members: #redis_macros::__private::reply_field(#ident_str, #names, elements.next().unwrap(), &v).map_err(parsing_error)?,
+                )*
+            }
+        },
+    };
+
+    Ok(quote! {
+        impl #impl_generics #redis::FromRedisValue for #ident #ty_generics #where_clause_extended {
+            fn from_redis_value(v: #redis::Value) -> ::std::result::Result<Self, #redis::ParsingError> {
+                #into_value
+                // The errors are converted to the ParsingError of the redis crate of the path
+                let parsing_error = |e: #redis_macros::DeserializeError| #redis::ParsingError::from(::std::string::ToString::to_string(&e));
+                #[allow(unused_mut, unused_variables)]
+                let mut elements = #redis_macros::__private::reply_elements(#ident_str, &v, #len).map_err(parsing_error)?.iter();
+                ::std::result::Result::Ok(#construct)
+            }
+        }
+    })
+}
@@ -0,0 +1,141 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::cell::Cell;
+
+/// The format of every JSON engine in the errors, so the messages don't change with the engine.
+pub(crate) const JSON_FORMAT: &str = "serde_json";
+
+/// JsonEngine is the JSON library used by the derives using `serde_json` and the [`JsonWith`](crate::JsonWith) wrapper.
+///
+/// By default, everything uses [`SerdeJson`]. With the `simd-json` or `sonic-rs` features, the [`DefaultJsonEngine`]
+/// of the derives becomes the faster `SimdJson` or `SonicRs` engine (preferring `sonic-rs` if both are enabled), so
+/// the same types can switch engines per build. The other wrappers keep using `serde_json`, a single value can use
+/// another engine with [`JsonWith`](crate::JsonWith) (e.g. `JsonWith<User, DefaultJsonEngine>`).
+///
+/// The engines have the same semantics and error messages as `serde_json`: if the faster engine fails, the value is
+/// deserialized again with `serde_json`, which returns the value or the error. The errors always report the
+/// `serde_json` format, with the same message, path and location.
+///
+/// This trait is sealed, it is only implemented by the engines of this crate.
+pub trait JsonEngine: sealed::Sealed {
+    /// The name of the engine (e.g. `sonic_rs`). The errors report `serde_json` for every engine.
+    const NAME: &'static str;
+
+    /// Deserialize a value from JSON.
+    fn from_str<T: DeserializeOwned>(s: &str) -> Result<T, serde_json::Error>;
+
+    /// Serialize a value to JSON.
+    fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, serde_json::Error>;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// How the serializer of the engine that is currently writing a value handles raw JSON (used by [`Lazy`](crate::Lazy)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RawJson {
+    /// The serializer is `serde_json`, which writes `RawValue` verbatim.
+    Verbatim,
+    /// The serializer of a faster engine, which fails on raw JSON so the engine falls back to `serde_json`.
+    #[cfg(any(feature = "simd-json", feature = "sonic-rs"))]
+    Unsupported,
+}
+
+thread_local! {
+    static RAW_JSON: Cell<Option<RawJson>> = const { Cell::new(None) };
+}
+
+/// Return how raw JSON is written by the current engine, or `None` outside of the engines (e.g. with `serde_yaml`).
+pub(crate) fn raw_json() -> Option<RawJson> {
+    RAW_JSON.with(Cell::get)
+}
+
+/// Run `f` with the raw JSON handling of the engine, restoring the previous one afterwards (even on panic).
+fn with_raw_json<R>(raw_json: RawJson, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<RawJson>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            RAW_JSON.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let _restore = Restore(RAW_JSON.with(|cell| cell.replace(Some(raw_json))));
+    f()
+}
+
+/// The [`serde_json`](https://docs.rs/serde_json) engine, used by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SerdeJson;
+
+impl sealed::Sealed for SerdeJson {}
+
+impl JsonEngine for SerdeJson {
+    const NAME: &'static str = "serde_json";
+
+    fn from_str<T: DeserializeOwned>(s: &str) -> Result<T, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+
+    fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, serde_json::Error> {
+        with_raw_json(RawJson::Verbatim, || serde_json::to_string(value))
+    }
+}
+
+/// The [`simd-json`](https://docs.rs/simd-json) engine, enabled with the `simd-json` feature.
+#[cfg(feature = "simd-json")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SimdJson;
+
+#[cfg(feature = "simd-json")]
+impl sealed::Sealed for SimdJson {}
+
+#[cfg(feature = "simd-json")]
+impl JsonEngine for SimdJson {
+    const NAME: &'static str = "simd_json";
+
+    fn from_str<T: DeserializeOwned>(s: &str) -> Result<T, serde_json::Error> {
+        // simd-json parses in place, so it needs a copy of the response
+        simd_json::serde::from_slice(&mut s.as_bytes().to_vec())
+            .or_else(|_| serde_json::from_str(s))
+    }
+
+    fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, serde_json::Error> {
+        with_raw_json(RawJson::Unsupported, || simd_json::serde::to_string(value))
+            .or_else(|_| SerdeJson::to_string(value))
+    }
+}
+
+/// The [`sonic-rs`](https://docs.rs/sonic-rs) engine, enabled with the `sonic-rs` feature.
+#[cfg(feature = "sonic-rs")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SonicRs;
+
+#[cfg(feature = "sonic-rs")]
+impl sealed::Sealed for SonicRs {}
+
+#[cfg(feature = "sonic-rs")]
+impl JsonEngine for SonicRs {
+    const NAME: &'static str = "sonic_rs";
+
+    fn from_str<T: DeserializeOwned>(s: &str) -> Result<T, serde_json::Error> {
+        sonic_rs::from_str(s).or_else(|_| serde_json::from_str(s))
+    }
+
+    fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, serde_json::Error> {
+        with_raw_json(RawJson::Unsupported, || sonic_rs::to_string(value))
+            .or_else(|_| SerdeJson::to_string(value))
+    }
+}
+
+/// The JSON engine of the derives, selected by the features.
+#[cfg(feature = "sonic-rs")]
+pub type DefaultJsonEngine = SonicRs;
+
+/// The JSON engine of the derives, selected by the features.
+#[cfg(all(feature = "simd-json", not(feature = "sonic-rs")))]
+pub type DefaultJsonEngine = SimdJson;
+
+/// The JSON engine of the derives, selected by the features.
+#[cfg(not(any(feature = "simd-json", feature = "sonic-rs")))]
+pub type DefaultJsonEngine = SerdeJson;
@@ -0,0 +1,112 @@
+/// Envelope is a small header to make values self-describing, so readers can decode them regardless of the writer's serializer.
+///
+/// The header has a fixed size of [`Envelope::HEADER_LEN`] bytes:
+///
+/// | Byte | Content                                                        |
+/// |------|----------------------------------------------------------------|
+/// | 0    | [`Envelope::MAGIC`] (`0xFE`, which can never start valid UTF-8) |
+/// | 1    | Format id (e.g. [`Envelope::FORMAT_JSON`])                     |
+/// | 2    | Flags (e.g. [`Envelope::FLAG_COMPRESSED`])                     |
+/// | 3-6  | Schema id as big-endian `u32`                                  |
+///
+/// When deriving, add `#[redis_envelope]` (or `#[redis_envelope(schema = 1)]`) to the type to write the values with the envelope.
+/// The derived `FromRedisValue` always detects the envelope and picks the serializer (from the primary and fallback serializers)
+/// by the format id, while values without the envelope are deserialized as before.
+/// With the `lz4` feature, `#[redis_compress]` also compresses the payload and sets [`Envelope::FLAG_COMPRESSED`].
+///
+/// ```rust
+/// use redis_macros::Envelope;
+///
+/// let bytes = Envelope::new(Envelope::FORMAT_JSON).with_schema(3).write(br#"{"id":1}"#);
+/// let (envelope, payload) = Envelope::parse(&bytes).unwrap().unwrap();
+/// assert_eq!(envelope.format, Envelope::FORMAT_JSON);
+/// assert_eq!(envelope.schema, 3);
+/// assert_eq!(payload, br#"{"id":1}"#);
+///
+/// // Values without the envelope are not parsed
+/// assert_eq!(Envelope::parse(br#"{"id":1}"#), Ok(None));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Envelope {
+    /// The id of the serializer of the payload.
+    pub format: u8,
+    /// The flags for the transformations of the payload.
+    pub flags: u8,
+    /// The id of the schema of the payload, defined by the application.
+    pub schema: u32,
+}
+
+impl Envelope {
+    /// The first byte of every value with an envelope.
+    pub const MAGIC: u8 = 0xFE;
+    /// The length of the envelope header in bytes.
+    pub const HEADER_LEN: usize = 7;
+
+    /// Format id of `serde_json`.
+    pub const FORMAT_JSON: u8 = 1;
+    /// Format id of `serde_yaml`.
+    pub const FORMAT_YAML: u8 = 2;
+    /// Format id of `toml`.
+    pub const FORMAT_TOML: u8 = 3;
+    /// Format id of `ron`.
+    pub const FORMAT_RON: u8 = 4;
+
+    /// Flag for payloads compressed with LZ4, with the size of the payload prepended as a little-endian `u32`.
+    /// The derived implementations write them with `#[redis_compress]` and read them with the `lz4` feature.
+    pub const FLAG_COMPRESSED: u8 = 0b01;
+    /// Flag for encrypted payloads. The derived implementations don't encrypt, so they reject these values.
+    pub const FLAG_ENCRYPTED: u8 = 0b10;
+
+    /// Create an envelope for the format id, without flags and with schema id 0.
+    pub fn new(format: u8) -> Self {
+        Envelope {
+            format,
+            flags: 0,
+            schema: 0,
+        }
+    }
+
+    /// Set the schema id of the envelope.
+    pub fn with_schema(self, schema: u32) -> Self {
+        Envelope { schema, ..self }
+    }
+
+    /// Set the flags of the envelope.
+    pub fn with_flags(self, flags: u8) -> Self {
+        Envelope { flags, ..self }
+    }
+
+    /// Write the header and the payload into a new buffer.
+    pub fn write(&self, payload: &[u8]) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(Self::HEADER_LEN + payload.len());
+        buf.push(Self::MAGIC);
+        buf.push(self.format);
+        buf.push(self.flags);
+        buf.extend_from_slice(&self.schema.to_be_bytes());
+        buf.extend_from_slice(payload);
+        buf
+    }
+
+    /// Parse the header from the value, returning the envelope and the payload.
+    ///
+    /// Returns `Ok(None)` if the value doesn't start with [`Envelope::MAGIC`], and an error if the header is truncated.
+    pub fn parse(bytes: &[u8]) -> Result<Option<(Envelope, &[u8])>, String> {
+        if bytes.first() != Some(&Self::MAGIC) {
+            return Ok(None);
+        }
+        if bytes.len() < Self::HEADER_LEN {
+            return Err(format!(
+                "envelope header is truncated ({} of {} bytes)",
+                bytes.len(),
+                Self::HEADER_LEN
+            ));
+        }
+        let (header, payload) = bytes.split_at(Self::HEADER_LEN);
+        let envelope = Envelope {
+            format: header[1],
+            flags: header[2],
+            schema: u32::from_be_bytes([header[3], header[4], header[5], header[6]]),
+        };
+        Ok(Some((envelope, payload)))
+    }
+}
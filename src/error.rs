@@ -0,0 +1,490 @@
+use redis::{ParsingError, Value};
+use std::fmt::{self, Display, Write};
+
+/// The maximum number of characters of the response that is included in the error messages.
+pub const PREVIEW_LIMIT: usize = 256;
+
+/// Structured error returned when a Redis response cannot be deserialized into a type.
+///
+/// It is produced by the derived [`FromRedisValue`](../redis/trait.FromRedisValue.html) implementations
+/// and the [`Json`](crate::Json) wrapper, and converts into redis' [`ParsingError`] (so it can be returned
+/// from `from_redis_value`). Unlike formatting the whole `redis::Value`, it only keeps a bounded preview
+/// of the response, so large payloads don't end up in your logs.
+///
+/// ```rust
+/// use redis::Value;
+/// use redis_macros::{DeserializeError, DeserializeErrorKind};
+///
+/// let err = DeserializeError::new("User", "serde_json", DeserializeErrorKind::UnexpectedValue, &Value::Nil);
+/// assert_eq!(err.target(), "User");
+/// assert_eq!(err.preview(), "nil");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeserializeError {
+    target: &'static str,
+    format: &'static str,
+    kind: Box<DeserializeErrorKind>,
+    preview: String,
+}
+
+/// The text that replaces the redacted parts of the error messages.
+pub const REDACTED: &str = "<redacted>";
+
+/// Parts of the response that should be hidden from the error messages.
+///
+/// When deriving, use `#[redis_redact]` on the sensitive fields or `#[redis_errors(redact_payload)]`
+/// on the type to hide the response entirely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Redaction {
+    /// Include the response and the serializer message in the errors.
+    #[default]
+    None,
+    /// Hide the values of these fields from the preview, and the serializer message if it failed inside one of them.
+    ///
+    /// The fields are hidden at any depth (e.g. in nested objects or RedisJSON responses), and their values are
+    /// also removed from the validation messages. The fields can only be removed from JSON responses, other
+    /// responses are hidden entirely, as well as their validation messages.
+    Fields(&'static [&'static str]),
+    /// Hide the response, and the serializer and validation messages.
+    Payload,
+}
+
+/// The reason why a [`DeserializeError`] happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DeserializeErrorKind {
+    /// The response was not a bulk string (e.g. it was nil or an array).
+    UnexpectedValue,
+    /// The response was not an array (e.g. for multi-key commands like `JSON.MGET`).
+    NotArray,
+    /// The response was not a RedisJSON response (wrapped in square brackets).
+    NotRedisJson,
+    /// The RedisJSON response did not contain exactly one match.
+    MatchCount(usize),
+    /// The response had an envelope that cannot be decoded (e.g. unknown format id or unsupported flags).
+    Envelope(String),
+    /// The response was not valid UTF-8.
+    InvalidUtf8(std::str::Utf8Error),
+    /// The response was deserialized, but the validator (set with `#[redis_validate]`) rejected it.
+    Validation(String),
+    /// The array response did not have the number of elements of the type (set with `#[redis_reply(tuple)]`).
+    Arity {
+        /// The number of fields of the type.
+        expected: usize,
+        /// The number of elements in the response.
+        actual: usize,
+    },
+    /// The element of an array response failed to convert to its field (set with `#[redis_reply(tuple)]`).
+    Field {
+        /// The name of the field.
+        name: &'static str,
+        /// The error message of the field type.
+        message: String,
+    },
+    /// The response was not one of the variants of an enum (set with `#[redis_enum]`).
+    UnknownVariant {
+        /// The value of the response.
+        value: String,
+        /// The values of the variants.
+        expected: Vec<String>,
+    },
+    /// The serializer failed to deserialize the response.
+    Serde {
+        /// The error message of the serializer, without the location.
+        message: String,
+        /// The path to the failing field (e.g. `addresses[0].Street`), if it is not the root.
+        path: Option<String>,
+        /// The line in the response where the error happened, if the serializer reports it.
+        line: Option<usize>,
+        /// The column in the response where the error happened, if the serializer reports it.
+        column: Option<usize>,
+    },
+    /// The value of one key in the response of a multi-key command failed (e.g. `JSON.MGET`).
+    Key {
+        /// The index of the key in the command.
+        index: usize,
+        /// The reason of the failure.
+        kind: Box<DeserializeErrorKind>,
+    },
+}
+
+impl DeserializeError {
+    /// Create a new error for the `target` type and `format`, keeping a bounded preview of the `value`.
+    pub fn new(
+        target: &'static str,
+        format: &'static str,
+        kind: DeserializeErrorKind,
+        value: &Value,
+    ) -> Self {
+        Self::with_redaction(target, format, kind, value, Redaction::None)
+    }
+
+    /// Create a new error like [`DeserializeError::new`], hiding the sensitive parts of the `value`.
+    ///
+    /// ```rust
+    /// use redis::Value;
+    /// use redis_macros::{DeserializeError, DeserializeErrorKind, Redaction};
+    ///
+    /// let value = Value::BulkString(br#"{"name":"Ziggy","password":"hunter2"}"#.to_vec());
+    /// let kind = DeserializeErrorKind::UnexpectedValue;
+    /// let err = DeserializeError::with_redaction("User", "serde_json", kind, &value, Redaction::Fields(&["password"]));
+    /// assert!(!err.to_string().contains("hunter2"));
+    /// ```
+    pub fn with_redaction(
+        target: &'static str,
+        format: &'static str,
+        mut kind: DeserializeErrorKind,
+        value: &Value,
+        redaction: Redaction,
+    ) -> Self {
+        // The values of the redacted fields, to hide them from the validation messages
+        let mut secrets = None;
+        let preview = match redaction {
+            Redaction::None => preview(value),
+            Redaction::Fields(_) | Redaction::Payload if *value == Value::Nil => preview(value),
+            Redaction::Fields(fields) => match scrub(value, fields) {
+                Some((scrubbed, values)) => {
+                    secrets = Some(values);
+                    preview(&scrubbed)
+                }
+                None => REDACTED.to_string(),
+            },
+            Redaction::Payload => REDACTED.to_string(),
+        };
+        match (&mut kind, redaction) {
+            (_, Redaction::None) => {}
+            (DeserializeErrorKind::Serde { message, path, .. }, Redaction::Fields(fields)) => {
+                // The field can be nested in the path (e.g. `[0].password` for RedisJSON responses)
+                let in_field = path.as_deref().is_some_and(|path| {
+                    path.split(['.', '[', ']'])
+                        .any(|segment| fields.contains(&segment))
+                });
+                if in_field {
+                    *message = REDACTED.to_string();
+                }
+            }
+            (DeserializeErrorKind::Validation(message), Redaction::Fields(_))
+                if secrets.is_some() =>
+            {
+                for secret in secrets.iter().flatten() {
+                    *message = message.replace(secret.as_str(), REDACTED);
+                }
+            }
+            (
+                DeserializeErrorKind::Serde { message, .. }
+                | DeserializeErrorKind::Validation(message),
+                _,
+            ) => *message = REDACTED.to_string(),
+            _ => {}
+        }
+        DeserializeError {
+            target,
+            format,
+            kind: Box::new(kind),
+            preview,
+        }
+    }
+
+    /// The name of the type that was deserialized into.
+    pub fn target(&self) -> &'static str {
+        self.target
+    }
+
+    /// The serializer that was used (e.g. `serde_json`).
+    pub fn format(&self) -> &'static str {
+        self.format
+    }
+
+    /// The reason of the failure.
+    pub fn kind(&self) -> &DeserializeErrorKind {
+        &self.kind
+    }
+
+    /// A preview of the response, truncated to [`PREVIEW_LIMIT`] characters.
+    pub fn preview(&self) -> &str {
+        &self.preview
+    }
+
+    /// Wrap the error of the value at `index` into [`DeserializeErrorKind::Key`].
+    #[cfg(feature = "json")]
+    pub(crate) fn at_key(self, index: usize) -> Self {
+        DeserializeError {
+            kind: Box::new(DeserializeErrorKind::Key {
+                index,
+                kind: self.kind,
+            }),
+            ..self
+        }
+    }
+
+    /// The path to the failing field, if the serializer failed inside a field.
+    pub fn path(&self) -> Option<&str> {
+        match self.kind.innermost() {
+            DeserializeErrorKind::Serde { path, .. } => path.as_deref(),
+            DeserializeErrorKind::Field { name, .. } => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The line and column of the failure, if the serializer reports it.
+    pub fn location(&self) -> Option<(usize, usize)> {
+        match *self.kind.innermost() {
+            DeserializeErrorKind::Serde {
+                line: Some(line),
+                column: Some(column),
+                ..
+            } => Some((line, column)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for DeserializeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeserializeErrorKind::UnexpectedValue => f.write_str("expected a bulk string"),
+            DeserializeErrorKind::NotArray => f.write_str("expected an array"),
+            DeserializeErrorKind::NotRedisJson => {
+                f.write_str("expected a RedisJSON response wrapped in square brackets")
+            }
+            DeserializeErrorKind::MatchCount(count) => {
+                write!(f, "expected exactly one match, got {count}")
+            }
+            DeserializeErrorKind::Envelope(message) => write!(f, "invalid envelope: {message}"),
+            DeserializeErrorKind::InvalidUtf8(e) => write!(f, "invalid UTF-8: {e}"),
+            DeserializeErrorKind::Validation(message) => write!(f, "validation failed: {message}"),
+            DeserializeErrorKind::Arity { expected, actual } => {
+                write!(f, "expected an array of {expected} elements, got {actual}")
+            }
+            DeserializeErrorKind::Field { name, message } => {
+                write!(f, "at field `{name}`: {message}")
+            }
+            DeserializeErrorKind::UnknownVariant { value, expected } => {
+                write!(f, "unknown variant `{value}`, expected one of ")?;
+                for (i, name) in expected.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "`{name}`")?;
+                }
+                Ok(())
+            }
+            DeserializeErrorKind::Serde {
+                message,
+                path,
+                line,
+                column,
+            } => {
+                f.write_str(message)?;
+                match (path, line.zip(*column)) {
+                    (Some(path), Some((line, column))) => {
+                        write!(f, " (at `{path}`, line {line} column {column})")
+                    }
+                    (Some(path), None) => write!(f, " (at `{path}`)"),
+                    (None, Some((line, column))) => write!(f, " (line {line} column {column})"),
+                    (None, None) => Ok(()),
+                }
+            }
+            DeserializeErrorKind::Key { index, kind } => write!(f, "at key {index}: {kind}"),
+        }
+    }
+}
+
+impl Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Response type not deserializable to {} with {}: {}. (response was {})",
+            self.target, self.format, self.kind, self.preview
+        )
+    }
+}
+
+impl std::error::Error for DeserializeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self.kind.innermost() {
+            DeserializeErrorKind::InvalidUtf8(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<DeserializeError> for ParsingError {
+    fn from(err: DeserializeError) -> ParsingError {
+        err.to_string().into()
+    }
+}
+
+impl DeserializeErrorKind {
+    /// The reason of the failure, without the [`DeserializeErrorKind::Key`] wrappers.
+    fn innermost(&self) -> &DeserializeErrorKind {
+        match self {
+            DeserializeErrorKind::Key { kind, .. } => kind.innermost(),
+            kind => kind,
+        }
+    }
+
+    /// Create a serde error kind from the error message of any serializer.
+    ///
+    /// Serializers usually append the location as `at line X column Y` (e.g. `serde_json` and `serde_yaml`),
+    /// if it is there, it is parsed into the line and column. The message is truncated to [`PREVIEW_LIMIT`]
+    /// characters, because it might contain parts of the response.
+    pub fn serde(error: impl Display, path: Option<String>) -> Self {
+        let message = error.to_string();
+        let (message, line, column) = match split_location(&message) {
+            Some((rest, line, column)) => (rest, Some(line), Some(column)),
+            None => (message.as_str(), None, None),
+        };
+        DeserializeErrorKind::Serde {
+            message: truncate(message),
+            path,
+            line,
+            column,
+        }
+    }
+
+    /// Create a field error kind from the error of the field type, truncated to [`PREVIEW_LIMIT`] characters.
+    pub fn field(name: &'static str, error: &ParsingError) -> Self {
+        let message = error.to_string();
+        // The message of the field type is already inside of an error of the same kind
+        let message = message
+            .strip_prefix("Incompatible type - ")
+            .unwrap_or(&message);
+        DeserializeErrorKind::Field {
+            name,
+            message: truncate(message),
+        }
+    }
+
+    /// Create an unknown variant error kind, truncating the `value` to [`PREVIEW_LIMIT`] characters.
+    pub fn unknown_variant<I>(value: impl Display, expected: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Display,
+    {
+        DeserializeErrorKind::UnknownVariant {
+            value: truncate(&value.to_string()),
+            expected: expected.into_iter().map(|name| name.to_string()).collect(),
+        }
+    }
+}
+
+fn split_location(message: &str) -> Option<(&str, usize, usize)> {
+    let (rest, location) = message.rsplit_once(" at line ")?;
+    let (line, column) = location.split_once(" column ")?;
+    Some((rest, line.parse().ok()?, column.parse().ok()?))
+}
+
+/// Replace the redacted fields in a JSON response (or a RedisJSON array of objects), if it can be parsed.
+fn scrub(value: &Value, fields: &[&str]) -> Option<(Value, Vec<String>)> {
+    let Value::BulkString(bytes) = value else {
+        return None;
+    };
+    let mut json: serde_json::Value = serde_json::from_slice(bytes).ok()?;
+    let objects = match &mut json {
+        serde_json::Value::Array(items) => items.iter_mut().collect(),
+        other => vec![other],
+    };
+    let mut secrets = Vec::new();
+    for object in objects {
+        if !object.is_object() {
+            return None;
+        }
+        scrub_fields(object, fields, &mut secrets);
+    }
+    Some((Value::BulkString(json.to_string().into_bytes()), secrets))
+}
+
+/// Replace the fields at any depth, and collect their values.
+fn scrub_fields(json: &mut serde_json::Value, fields: &[&str], secrets: &mut Vec<String>) {
+    match json {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if fields.contains(&key.as_str()) {
+                    collect_scalars(value, secrets);
+                    *value = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    scrub_fields(value, fields, secrets);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                scrub_fields(item, fields, secrets);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collect the text of the strings, numbers and booleans in the value.
+fn collect_scalars(json: &serde_json::Value, secrets: &mut Vec<String>) {
+    match json {
+        serde_json::Value::Null => {}
+        serde_json::Value::String(s) if s.is_empty() => {}
+        serde_json::Value::String(s) => secrets.push(s.clone()),
+        serde_json::Value::Object(map) => map
+            .values()
+            .for_each(|value| collect_scalars(value, secrets)),
+        serde_json::Value::Array(items) => {
+            items.iter().for_each(|item| collect_scalars(item, secrets))
+        }
+        other => secrets.push(other.to_string()),
+    }
+}
+
+/// Writer that stops writing after the limit is reached.
+struct Bounded {
+    buf: String,
+    remaining: usize,
+    truncated: bool,
+}
+
+impl Write for Bounded {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match s.char_indices().nth(self.remaining) {
+            Some((end, _)) => {
+                self.buf.push_str(&s[..end]);
+                self.remaining = 0;
+                self.truncated = true;
+                Err(fmt::Error)
+            }
+            None => {
+                self.buf.push_str(s);
+                self.remaining -= s.chars().count();
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Bounded {
+    fn new() -> Self {
+        Bounded {
+            buf: String::new(),
+            remaining: PREVIEW_LIMIT,
+            truncated: false,
+        }
+    }
+}
+
+fn truncate(s: &str) -> String {
+    let mut w = Bounded::new();
+    if w.write_str(s).is_err() {
+        w.buf.push_str("...");
+    }
+    w.buf
+}
+
+/// Format the value as a preview, truncated to [`PREVIEW_LIMIT`] characters.
+pub(crate) fn preview(value: &Value) -> String {
+    let mut w = Bounded::new();
+    let _ = write!(w, "{value:?}");
+    if w.truncated {
+        w.buf.push_str("...");
+        if let Value::BulkString(bytes) = value {
+            let _ = write!(w.buf, " ({} bytes)", bytes.len());
+        }
+    }
+    w.buf
+}
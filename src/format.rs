@@ -0,0 +1,52 @@
+use redis::{FromRedisValue, ParsingError, Value};
+
+/// Parse Redis responses, returning which serializer could deserialize the value.
+///
+/// It is implemented by the [`FromRedisValue`](crate::FromRedisValue) derive. It is mostly useful with fallback
+/// serializers (`#[redis_serializer(serde_json, fallback(serde_yaml))]`), to know which format the value was stored in.
+pub trait FromRedisValueWithFormat: Sized {
+    /// Parse the value, returning the name of the serializer that succeeded (e.g. `serde_json`).
+    fn from_redis_value_with_format(v: Value) -> Result<(Self, &'static str), ParsingError>;
+}
+
+/// WithFormat is a wrapper to return the name of the serializer that deserialized the value.
+///
+/// This is useful when migrating between serializers: the type reads every format in the fallback chain,
+/// but you can find the values that are still in an old format and rewrite them.
+///
+/// ```rust,no_run
+/// # use redis::{Client, Commands, RedisResult};
+/// use redis_macros::{FromRedisValue, ToRedisArgs, WithFormat};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+/// #[redis_serializer(serde_json, fallback(serde_yaml))]
+/// struct User { id: u32 }
+///
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// let WithFormat(user, format): WithFormat<User> = con.get("user")?;
+/// if format != "serde_json" {
+///     // Writes always use the primary serializer
+///     let _: () = con.set("user", &user)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct WithFormat<T>(
+    /// The deserialized value
+    pub T,
+    /// The name of the serializer that deserialized the value
+    pub &'static str,
+);
+
+impl<T> FromRedisValue for WithFormat<T>
+where
+    T: FromRedisValueWithFormat,
+{
+    fn from_redis_value(v: Value) -> Result<WithFormat<T>, ParsingError> {
+        T::from_redis_value_with_format(v).map(|(value, format)| WithFormat(value, format))
+    }
+}
@@ -0,0 +1,234 @@
+use crate::{__private, DeserializeError, DeserializeErrorKind, Redaction};
+use redis::{FromRedisValue, ParsingError, RedisWrite, ToRedisArgs, ToSingleRedisArg, Value};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Implement the conversions of the wrapper, which are the same for every format.
+macro_rules! impl_wrapper {
+    ($wrapper:ident) => {
+        impl<T> $wrapper<T> {
+            /// Return the inner value.
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        impl<T> From<T> for $wrapper<T> {
+            fn from(value: T) -> Self {
+                $wrapper(value)
+            }
+        }
+
+        impl<T> ::std::ops::Deref for $wrapper<T> {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                &self.0
+            }
+        }
+
+        impl<T> ::std::ops::DerefMut for $wrapper<T> {
+            fn deref_mut(&mut self) -> &mut T {
+                &mut self.0
+            }
+        }
+
+        impl<T> ToSingleRedisArg for $wrapper<T> where T: Serialize {}
+    };
+}
+
+/// Return the bytes of the response, which must be a bulk string.
+fn bulk_string<'a, T>(v: &'a Value, format: &'static str) -> Result<&'a [u8], DeserializeError> {
+    match v {
+        Value::BulkString(bytes) => Ok(bytes),
+        _ => Err(DeserializeError::new(
+            ::std::any::type_name::<T>(),
+            format,
+            DeserializeErrorKind::UnexpectedValue,
+            v,
+        )),
+    }
+}
+
+/// Yaml is a wrapper to store any serde type as YAML with the plain Redis commands (e.g. `GET` and `SET`).
+///
+/// Because of the orphan rule, you cannot derive `FromRedisValue` and `ToRedisArgs` for the types of other crates.
+/// Instead, you can wrap them into `Yaml`, which only requires them to be `Serialize` and `Deserialize`.
+///
+/// ```rust,no_run
+/// # use redis::{Client, Commands, RedisResult};
+/// use redis_macros::Yaml;
+/// use std::collections::BTreeMap;
+///
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// let limits = BTreeMap::from([("requests".to_string(), 100)]);
+/// let _: () = con.set("limits", Yaml(&limits))?;  // => "requests: 100\n"
+/// let Yaml(stored): Yaml<BTreeMap<String, u32>> = con.get("limits")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Missing keys return `Nil`, use `Option<Yaml<T>>` to handle them.
+///
+/// ***WARNING: `ToRedisArgs` panics if the underlying serialization fails.***
+#[cfg(feature = "yaml")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Yaml<T>(
+    /// The inner value
+    pub T,
+);
+
+#[cfg(feature = "yaml")]
+impl_wrapper!(Yaml);
+
+#[cfg(feature = "yaml")]
+impl<T> ToRedisArgs for Yaml<T>
+where
+    T: Serialize,
+{
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        let buf = serde_yaml::to_string(&self.0).unwrap();
+        out.write_arg(buf.as_bytes())
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl<T> FromRedisValue for Yaml<T>
+where
+    T: DeserializeOwned,
+{
+    fn from_redis_value(v: Value) -> Result<Yaml<T>, ParsingError> {
+        let target = ::std::any::type_name::<T>();
+        let format = "serde_yaml";
+        let bytes = bulk_string::<T>(&v, format)?;
+        let s = ::std::str::from_utf8(bytes).map_err(|e| {
+            DeserializeError::new(target, format, DeserializeErrorKind::InvalidUtf8(e), &v)
+        })?;
+        let deser = __private::from_str(target, format, Redaction::None, &v, s, |s| {
+            serde_yaml::from_str(s)
+        })?;
+        Ok(Yaml(deser))
+    }
+}
+
+/// MsgPack is a wrapper to store any serde type as [MessagePack](https://msgpack.org) with the plain Redis commands.
+///
+/// Like [`Yaml`], it works for the types of other crates, which only have to be `Serialize` and `Deserialize`.
+/// The structs are written as maps with the field names (see `rmp_serde::to_vec_named`), so the fields can be reordered.
+///
+/// ```rust,no_run
+/// # use redis::{Client, Commands, RedisResult};
+/// use redis_macros::MsgPack;
+///
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// let _: () = con.set("ids", MsgPack(vec![1, 2, 3]))?;
+/// let MsgPack(ids): MsgPack<Vec<u32>> = con.get("ids")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ***WARNING: `ToRedisArgs` panics if the underlying serialization fails.***
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MsgPack<T>(
+    /// The inner value
+    pub T,
+);
+
+#[cfg(feature = "msgpack")]
+impl_wrapper!(MsgPack);
+
+#[cfg(feature = "msgpack")]
+impl<T> ToRedisArgs for MsgPack<T>
+where
+    T: Serialize,
+{
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        let buf = rmp_serde::to_vec_named(&self.0).unwrap();
+        out.write_arg(&buf)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl<T> FromRedisValue for MsgPack<T>
+where
+    T: DeserializeOwned,
+{
+    fn from_redis_value(v: Value) -> Result<MsgPack<T>, ParsingError> {
+        let target = ::std::any::type_name::<T>();
+        let format = "rmp_serde";
+        let bytes = bulk_string::<T>(&v, format)?;
+        let deser = __private::from_slice(target, format, Redaction::None, &v, bytes, |bytes| {
+            rmp_serde::from_slice(bytes)
+        })?;
+        Ok(MsgPack(deser))
+    }
+}
+
+/// Cbor is a wrapper to store any serde type as [CBOR](https://cbor.io) with the plain Redis commands.
+///
+/// Like [`Yaml`], it works for the types of other crates, which only have to be `Serialize` and `Deserialize`.
+///
+/// ```rust,no_run
+/// # use redis::{Client, Commands, RedisResult};
+/// use redis_macros::Cbor;
+///
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// let _: () = con.set("ids", Cbor(vec![1, 2, 3]))?;
+/// let Cbor(ids): Cbor<Vec<u32>> = con.get("ids")?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ***WARNING: `ToRedisArgs` panics if the underlying serialization fails.***
+#[cfg(feature = "cbor")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cbor<T>(
+    /// The inner value
+    pub T,
+);
+
+#[cfg(feature = "cbor")]
+impl_wrapper!(Cbor);
+
+#[cfg(feature = "cbor")]
+impl<T> ToRedisArgs for Cbor<T>
+where
+    T: Serialize,
+{
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        let mut buf = Vec::new();
+        ciborium::into_writer(&self.0, &mut buf).unwrap();
+        out.write_arg(&buf)
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl<T> FromRedisValue for Cbor<T>
+where
+    T: DeserializeOwned,
+{
+    fn from_redis_value(v: Value) -> Result<Cbor<T>, ParsingError> {
+        let target = ::std::any::type_name::<T>();
+        let format = "ciborium";
+        let bytes = bulk_string::<T>(&v, format)?;
+        let deser = __private::from_slice(target, format, Redaction::None, &v, bytes, |bytes| {
+            ciborium::from_reader(bytes)
+        })?;
+        Ok(Cbor(deser))
+    }
+}
@@ -0,0 +1,395 @@
+//! A serde deserializer for the text replies of `INFO`, `CLIENT INFO` and `CLIENT LIST`.
+//!
+//! Use it as the serializer of the [`FromRedisValue`](crate::FromRedisValue) derive to decode these replies
+//! into typed structs:
+//!
+//! ```rust,no_run
+//! use redis_macros::FromRedisValue;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Server { redis_version: String, uptime_in_seconds: u64 }
+//!
+//! #[derive(Deserialize)]
+//! struct Keyspace { keys: u64, expires: u64 }
+//!
+//! #[derive(Deserialize, FromRedisValue)]
+//! #[redis_serializer(redis_macros::info)]
+//! struct Info { server: Server, connected_clients: u32, db0: Option<Keyspace> }
+//!
+//! #[derive(Deserialize, FromRedisValue)]
+//! #[redis_serializer(redis_macros::info)]
+//! struct ClientInfo { id: u64, addr: String, name: Option<String>, age: u64 }
+//!
+//! #[derive(Deserialize, FromRedisValue)]
+//! #[redis_serializer(redis_macros::info)]
+//! struct ClientList(Vec<ClientInfo>);
+//!
+//! # fn main () -> redis::RedisResult<()> {
+//! # let client = redis::Client::open("redis://localhost:6379/")?;
+//! # let mut con = client.get_connection()?;
+//! let info: Info = redis::cmd("INFO").query(&mut con)?;
+//! let client: ClientInfo = redis::cmd("CLIENT").arg("INFO").query(&mut con)?;
+//! let ClientList(clients) = redis::cmd("CLIENT").arg("LIST").query(&mut con)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The reply is read as a map of every `key:value` (or `key=value`) pair. Structs can also have fields named after
+//! the `# Section` headers (in lowercase, e.g. `server` or `keyspace`), which contain the pairs of that section.
+//! Values with comma-separated `key=value` pairs (e.g. `db0:keys=1,expires=0`) are read as maps or structs, and other
+//! comma-separated values as sequences. Empty values (e.g. `name=`) are read as `None`.
+//!
+//! A sequence of structs reads each line as a struct, for `CLIENT LIST`.
+//!
+//! `MEMORY STATS` is not a text reply, read it as a `HashMap` or a `Vec` of pairs instead.
+
+use serde::de::{
+    self, value::BorrowedStrDeserializer, DeserializeSeed, Deserializer, MapAccess, SeqAccess,
+    Unexpected, Visitor,
+};
+use serde::forward_to_deserialize_any;
+use std::fmt::{self, Display};
+
+/// Deserialize an `INFO`, `CLIENT INFO` or `CLIENT LIST` reply.
+pub fn from_str<'a, T>(s: &'a str) -> Result<T, Error>
+where
+    T: de::Deserialize<'a>,
+{
+    T::deserialize(Document::parse(s)?)
+}
+
+/// The error of the [`info`](self) deserializer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error(String);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+type Pairs<'a> = Vec<(&'a str, &'a str)>;
+
+/// The parsed reply, with the pairs of every line and every section.
+struct Document<'a> {
+    lines: Vec<Pairs<'a>>,
+    sections: Vec<(String, Pairs<'a>)>,
+}
+
+impl<'a> Document<'a> {
+    fn parse(s: &'a str) -> Result<Self, Error> {
+        let mut lines = Vec::new();
+        let mut sections = Vec::new();
+        for (number, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if let Some(header) = line.strip_prefix('#') {
+                let name = header.trim().to_lowercase().replace(' ', "_");
+                sections.push((name, Vec::new()));
+                continue;
+            }
+            if line.is_empty() {
+                continue;
+            }
+            let pairs = parse_line(line).map_err(|column| {
+                Error(format!(
+                    "expected `key:value` or `key=value` pairs at line {} column {}",
+                    number + 1,
+                    column + 1
+                ))
+            })?;
+            if let Some((_, section)) = sections.last_mut() {
+                section.extend(pairs.iter().copied());
+            }
+            lines.push(pairs);
+        }
+        Ok(Document { lines, sections })
+    }
+
+    fn pairs(&self) -> impl Iterator<Item = (&'a str, Node<'a>)> + '_ {
+        self.lines
+            .iter()
+            .flatten()
+            .map(|&(key, value)| (key, Node::Value(value)))
+    }
+}
+
+/// Parse a `key:value` line (`INFO`) or a line of space-separated `key=value` pairs (`CLIENT LIST`),
+/// returning the column of the invalid pair on failure.
+fn parse_line(line: &str) -> Result<Pairs<'_>, usize> {
+    match line.find([':', '=']) {
+        Some(index) if line[index..].starts_with(':') => {
+            Ok(vec![(&line[..index], &line[index + 1..])])
+        }
+        Some(_) => line
+            .split(' ')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                pair.split_once('=')
+                    .ok_or_else(|| pair.as_ptr() as usize - line.as_ptr() as usize)
+            })
+            .collect(),
+        None => Err(0),
+    }
+}
+
+/// Parse comma-separated `key=value` pairs (e.g. `keys=1,expires=0`).
+fn parse_nested(s: &str) -> Result<Pairs<'_>, Error> {
+    s.split(',')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            pair.split_once('=')
+                .ok_or_else(|| Error(format!("expected `key=value` pairs, got `{pair}`")))
+        })
+        .collect()
+}
+
+impl<'de> Deserializer<'de> for Document<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_map(Entries::new(self.pairs()))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        // The fields named after a section get the pairs of the section, unless there is a key with the same name
+        let sections = self.sections.iter().filter(|(name, _)| {
+            fields.contains(&name.as_str()) && !self.pairs().any(|(key, _)| key == name)
+        });
+        let sections = sections.filter_map(|(name, pairs)| {
+            let field = fields.iter().find(|field| **field == name.as_str())?;
+            Some((*field, Node::Pairs(pairs.clone())))
+        });
+        visitor.visit_map(Entries::new(self.pairs().chain(sections)))
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(Elements(self.lines.into_iter().map(Node::Pairs)))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct tuple tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// A value of the reply, or the pairs of a line, a section or a nested value.
+enum Node<'a> {
+    Value(&'a str),
+    Pairs(Pairs<'a>),
+}
+
+impl Node<'_> {
+    fn parse<T: std::str::FromStr>(s: &str, visitor: &dyn de::Expected) -> Result<T, Error> {
+        s.parse()
+            .map_err(|_| de::Error::invalid_value(Unexpected::Str(s), visitor))
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self {
+                    Node::Value(s) => {
+                        let value = Node::parse(s, &visitor)?;
+                        visitor.$visit(value)
+                    }
+                    node => node.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Node<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Node::Value(s) => visitor.visit_borrowed_str(s),
+            Node::Pairs(pairs) => visitor.visit_map(Entries::new(
+                pairs
+                    .into_iter()
+                    .map(|(key, value)| (key, Node::Value(value))),
+            )),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Node::Value("1" | "yes" | "true") => visitor.visit_bool(true),
+            Node::Value("0" | "no" | "false") => visitor.visit_bool(false),
+            Node::Value(s) => Err(de::Error::invalid_value(Unexpected::Str(s), &visitor)),
+            node => node.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Node::Value("") => visitor.visit_none(),
+            node => visitor.visit_some(node),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Node::Value(s) => visitor.visit_seq(Elements(
+                s.split(',').filter(|s| !s.is_empty()).map(Node::Value),
+            )),
+            node => node.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self {
+            Node::Value(s) => Node::Pairs(parse_nested(s)?).deserialize_any(visitor),
+            node => node.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self {
+            Node::Value(s) => visitor.visit_enum(BorrowedStrDeserializer::new(s)),
+            node => node.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        char str string bytes byte_buf unit_struct tuple_struct identifier
+    }
+}
+
+/// The pairs of a map, with the value of the last key.
+struct Entries<'a, I> {
+    iter: I,
+    value: Option<Node<'a>>,
+}
+
+impl<'a, I> Entries<'a, I> {
+    fn new(iter: I) -> Self {
+        Entries { iter, value: None }
+    }
+}
+
+impl<'de, I> MapAccess<'de> for Entries<'de, I>
+where
+    I: Iterator<Item = (&'de str, Node<'de>)>,
+{
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        let Some((key, value)) = self.iter.next() else {
+            return Ok(None);
+        };
+        self.value = Some(value);
+        seed.deserialize(BorrowedStrDeserializer::new(key))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| Error("value requested before the key".to_string()))?;
+        seed.deserialize(value)
+    }
+}
+
+/// The elements of a sequence.
+struct Elements<I>(I);
+
+impl<'de, I> SeqAccess<'de> for Elements<I>
+where
+    I: Iterator<Item = Node<'de>>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        self.0.next().map(|node| seed.deserialize(node)).transpose()
+    }
+}
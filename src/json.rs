@@ -1,5 +1,13 @@
-use redis::{ParsingError, Value};
-use serde::de::DeserializeOwned;
+use crate::{
+    __private, engine::JSON_FORMAT, DeserializeError, DeserializeErrorKind, JsonEngine, Redaction,
+    SerdeJson,
+};
+use redis::{ParsingError, RedisWrite, ToRedisArgs, ToSingleRedisArg, Value};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
 
 /// Json struct is a wrapper to handle the return types from the RedisJSON commands.
 ///
@@ -22,7 +30,7 @@ use serde::de::DeserializeOwned;
 /// # }
 /// ```
 ///
-/// Instead, `Json` implements the `FromRedisValue` trait, removes the square brackets and deserializes from JSON.
+/// Instead, `Json` implements the `FromRedisValue` trait, takes the value from the square brackets and deserializes from JSON.
 /// For this your type don't even have to implement `FromRedisValue`, it only requires to be serde `Deserialize`-able.
 ///
 /// ```rust,no_run
@@ -42,41 +50,663 @@ use serde::de::DeserializeOwned;
 /// # }
 /// ```
 ///
-/// This command is designed to use RedisJSON commands. You could probably use this type
-/// to parse normal command outputs, but it removes the first and last character
-/// so it is not recommended.
+/// This command is designed to use RedisJSON commands, and it requires the JSONPath to match exactly one value:
+/// it fails with "expected exactly one match, got N" otherwise. To get every match of the JSONPath, use [`JsonAll`].
 ///
-#[derive(Debug)]
+/// `Json` also implements `ToRedisArgs` (serializing the inner value to JSON as a single argument) and it serializes
+/// transparently with serde, so you can pass types that redis-rs already handles (e.g. Vecs) to RedisJSON commands.
+/// If you don't want to move or clone the value, use [`JsonRef`] instead.
+///
+/// ```rust,no_run
+/// # use redis::{Client, JsonCommands, RedisResult};
+/// # use redis_macros::Json;
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// let _: () = con.json_set("ids", "$", &Json(vec![1, 2, 3]))?;
+/// let ids: Json<Vec<u32>> = con.json_get("ids", "$")?;
+/// assert_eq!(*ids, vec![1, 2, 3]);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// `Json` always uses `serde_json`, use [`JsonWith`] to read and write the value with another [`JsonEngine`].
+///
+/// ***WARNING: `ToRedisArgs` panics if the underlying serialization fails.***
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
 pub struct Json<T>(
     /// The inner type to deserialize
     pub T,
 );
 
+impl<T> Json<T> {
+    /// Return the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Json<T> {
+    fn from(value: T) -> Self {
+        Json(value)
+    }
+}
+
+impl<T> Deref for Json<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Json<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> ToRedisArgs for Json<T>
+where
+    T: Serialize,
+{
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        JsonRef(&self.0).write_redis_args(out)
+    }
+}
+
+impl<T> ToSingleRedisArg for Json<T> where T: Serialize {}
+
+/// JsonWith is the version of [`Json`] that reads and writes the value with the [`JsonEngine`] `E`.
+///
+/// It behaves like `Json` (and defaults to the same [`SerdeJson`] engine), with the same semantics and error messages
+/// for every engine. Use `JsonWith<T, DefaultJsonEngine>` to follow the engine selected by the features, like the
+/// derives, or name the engine to always use it (e.g. `JsonWith<User, SonicRs>` with the `sonic-rs` feature).
+///
+/// ```rust
+/// use redis::{FromRedisValue, Value};
+/// use redis_macros::{DefaultJsonEngine, JsonWith};
+///
+/// let value = Value::BulkString(b"[1]".to_vec());
+/// let id = JsonWith::<u32, DefaultJsonEngine>::from_redis_value(value).unwrap();
+/// assert_eq!(*id, 1);
+/// assert_eq!(id.into_inner(), 1);
+/// ```
+///
+/// ***WARNING: `ToRedisArgs` panics if the underlying serialization fails.***
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct JsonWith<T, E = SerdeJson> {
+    value: T,
+    #[serde(skip)]
+    engine: PhantomData<E>,
+}
+
+impl<T, E> JsonWith<T, E> {
+    /// Wrap the value, to write it with the engine `E`.
+    pub fn new(value: T) -> Self {
+        JsonWith {
+            value,
+            engine: PhantomData,
+        }
+    }
+
+    /// Return the inner value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, E> From<T> for JsonWith<T, E> {
+    fn from(value: T) -> Self {
+        JsonWith::new(value)
+    }
+}
+
+impl<T, E> From<Json<T>> for JsonWith<T, E> {
+    fn from(Json(value): Json<T>) -> Self {
+        JsonWith::new(value)
+    }
+}
+
+impl<T, E> Deref for JsonWith<T, E> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, E> DerefMut for JsonWith<T, E> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T, E> ToRedisArgs for JsonWith<T, E>
+where
+    T: Serialize,
+    E: JsonEngine,
+{
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        let buf = E::to_string(&self.value).unwrap();
+        out.write_arg(buf.as_bytes())
+    }
+}
+
+impl<T, E> ToSingleRedisArg for JsonWith<T, E>
+where
+    T: Serialize,
+    E: JsonEngine,
+{
+}
+
+/// JsonRef is a borrowing version of [`Json`], to pass values to Redis commands as JSON without cloning them.
+///
+/// ```rust,no_run
+/// # use redis::{Client, Commands, RedisResult};
+/// # use redis_macros::{Json, JsonRef};
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// let ids = vec![1, 2, 3];
+/// let _: () = con.set("ids", JsonRef(&ids))?;  // => "[1,2,3]"
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ***WARNING: `ToRedisArgs` panics if the underlying serialization fails.***
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(transparent)]
+pub struct JsonRef<'a, T: ?Sized>(
+    /// The reference to the value to serialize
+    pub &'a T,
+);
+
+impl<T: ?Sized> Clone for JsonRef<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for JsonRef<'_, T> {}
+
+impl<T: ?Sized> Deref for JsonRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.0
+    }
+}
+
+impl<'a, T: ?Sized> From<&'a T> for JsonRef<'a, T> {
+    fn from(value: &'a T) -> Self {
+        JsonRef(value)
+    }
+}
+
+impl<T> ToRedisArgs for JsonRef<'_, T>
+where
+    T: Serialize + ?Sized,
+{
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        let buf = SerdeJson::to_string(self.0).unwrap();
+        out.write_arg(buf.as_bytes())
+    }
+}
+
+impl<T> ToSingleRedisArg for JsonRef<'_, T> where T: Serialize + ?Sized {}
+
 impl<T> ::redis::FromRedisValue for Json<T>
 where
     T: DeserializeOwned,
 {
     fn from_redis_value(v: Value) -> Result<Json<T>, ParsingError> {
+        Ok(Json(from_single_match::<T, SerdeJson>(&v)?))
+    }
+}
+
+impl<T, E> ::redis::FromRedisValue for JsonWith<T, E>
+where
+    T: DeserializeOwned,
+    E: JsonEngine,
+{
+    fn from_redis_value(v: Value) -> Result<JsonWith<T, E>, ParsingError> {
+        Ok(JsonWith::new(from_single_match::<T, E>(&v)?))
+    }
+}
+
+/// Deserialize a RedisJSON response with the engine `E`, which must contain exactly one match.
+fn from_single_match<T, E>(v: &Value) -> Result<T, DeserializeError>
+where
+    T: DeserializeOwned,
+    E: JsonEngine,
+{
+    let target = ::std::any::type_name::<T>();
+    let error = |kind| DeserializeError::new(target, JSON_FORMAT, kind, v);
+
+    let Value::BulkString(bytes) = v else {
+        return Err(error(DeserializeErrorKind::UnexpectedValue));
+    };
+
+    let s =
+        ::std::str::from_utf8(bytes).map_err(|e| error(DeserializeErrorKind::InvalidUtf8(e)))?;
+    if !(s.starts_with('[') && s.ends_with(']')) {
+        return Err(error(DeserializeErrorKind::NotRedisJson));
+    }
+    __private::from_redis_json::<T, E>(target, Redaction::None, v, s)
+}
+
+/// JsonAll is a wrapper to get every match of a JSONPath from the RedisJSON commands.
+///
+/// RedisJSON returns an array of all matches of the JSONPath (e.g. `$..price`), `JsonAll` deserializes them into a `Vec`.
+/// Unlike [`Json`], it doesn't fail if there are zero or multiple matches.
+///
+/// ```rust,no_run
+/// # use redis::{Client, JsonCommands, RedisResult};
+/// # use redis_macros::JsonAll;
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// # let _: () = con.json_set("order", "$", &r#"{ "items": [{ "price": 1 }, { "price": 2 }] }"#)?;
+/// let JsonAll(prices): JsonAll<u32> = con.json_get("order", "$..price")?;  // => vec![1, 2]
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct JsonAll<T>(
+    /// The deserialized matches
+    pub Vec<T>,
+);
+
+impl<T> JsonAll<T> {
+    /// Return the matches.
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> From<Vec<T>> for JsonAll<T> {
+    fn from(value: Vec<T>) -> Self {
+        JsonAll(value)
+    }
+}
+
+impl<T> Deref for JsonAll<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for JsonAll<T> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        &mut self.0
+    }
+}
+
+impl<T> IntoIterator for JsonAll<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<T> ::redis::FromRedisValue for JsonAll<T>
+where
+    T: DeserializeOwned,
+{
+    fn from_redis_value(v: Value) -> Result<JsonAll<T>, ParsingError> {
+        let target = ::std::any::type_name::<T>();
+        let error = |kind| DeserializeError::new(target, JSON_FORMAT, kind, &v);
+
         let Value::BulkString(bytes) = &v else {
-            return Err(format!(
-                "Response type in JSON was not deserializable. (response was {v:?})"
-            )
-            .into());
+            return Err(error(DeserializeErrorKind::UnexpectedValue).into());
         };
 
-        let s = ::std::str::from_utf8(bytes).map_err(|e| {
-            format!("Response type in JSON is invalid UTF-8: {e}. (response was {v:?})")
+        let s = ::std::str::from_utf8(bytes)
+            .map_err(|e| error(DeserializeErrorKind::InvalidUtf8(e)))?;
+        if !(s.starts_with('[') && s.ends_with(']')) {
+            return Err(error(DeserializeErrorKind::NotRedisJson).into());
+        }
+        let format = JSON_FORMAT;
+        let deser = __private::from_str(target, format, Redaction::None, &v, s, |s| {
+            SerdeJson::from_str(s)
         })?;
-        let mut ch = s.chars();
-        if !(ch.next() == Some('[') && ch.next_back() == Some(']')) {
-            return Err(format!(
-                "Response type in JSON was not deserializable. (response was {v:?})"
-            )
-            .into());
+        Ok(JsonAll(deser))
+    }
+}
+
+/// JsonMulti is a wrapper to handle the response of `JSON.MGET`, with the match of the JSONPath for every key.
+///
+/// Keys that don't exist are returned as `None`, the other values must have exactly one match (like [`Json`]).
+/// If one of the values cannot be deserialized, the error contains the index of its key.
+///
+/// ```rust,no_run
+/// # use redis::{Client, JsonCommands, RedisResult};
+/// # use redis_macros::JsonMulti;
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// let JsonMulti(ids): JsonMulti<u32> = con.json_mget(&["user:1", "user:2"], "$.id")?;  // => vec![Some(1), None]
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct JsonMulti<T>(
+    /// The deserialized values, in the order of the keys
+    pub Vec<Option<T>>,
+);
+
+impl<T> JsonMulti<T> {
+    /// Return the values.
+    pub fn into_inner(self) -> Vec<Option<T>> {
+        self.0
+    }
+}
+
+impl<T> From<Vec<Option<T>>> for JsonMulti<T> {
+    fn from(value: Vec<Option<T>>) -> Self {
+        JsonMulti(value)
+    }
+}
+
+impl<T> Deref for JsonMulti<T> {
+    type Target = Vec<Option<T>>;
+
+    fn deref(&self) -> &Vec<Option<T>> {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for JsonMulti<T> {
+    fn deref_mut(&mut self) -> &mut Vec<Option<T>> {
+        &mut self.0
+    }
+}
+
+impl<T> IntoIterator for JsonMulti<T> {
+    type Item = Option<T>;
+    type IntoIter = std::vec::IntoIter<Option<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<T> ::redis::FromRedisValue for JsonMulti<T>
+where
+    T: DeserializeOwned,
+{
+    fn from_redis_value(v: Value) -> Result<JsonMulti<T>, ParsingError> {
+        let Value::Array(values) = &v else {
+            let target = ::std::any::type_name::<T>();
+            let kind = DeserializeErrorKind::NotArray;
+            return Err(DeserializeError::new(target, JSON_FORMAT, kind, &v).into());
+        };
+        let deser = values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| match value {
+                Value::Nil => Ok(None),
+                value => from_single_match::<T, SerdeJson>(value)
+                    .map(Some)
+                    .map_err(|e| e.at_key(index)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(JsonMulti(deser))
+    }
+}
+
+/// JsonMSet builds the arguments of `JSON.MSET` from `(key, path, &value)` triples, serializing the values to JSON.
+///
+/// ```rust,no_run
+/// # use redis::{Client, RedisResult};
+/// # use redis_macros::JsonMSet;
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// let (first, second) = (vec![1, 2], vec![3]);
+/// let mset = JsonMSet::new()
+///     .set("user:1", "$.ids", &first)
+///     .set("user:2", "$.ids", &second);
+/// let _: () = mset.cmd().query(&mut con)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// It can also be collected from an iterator of triples, and passed as arguments with `redis::cmd("JSON.MSET").arg(&mset)`.
+///
+/// ***WARNING: `ToRedisArgs` panics if the underlying serialization fails.***
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct JsonMSet<'a, K, P, T: ?Sized> {
+    triples: Vec<(K, P, &'a T)>,
+}
+
+impl<'a, K, P, T: ?Sized> JsonMSet<'a, K, P, T> {
+    /// Create an empty `JSON.MSET` builder.
+    pub fn new() -> Self {
+        JsonMSet {
+            triples: Vec::new(),
+        }
+    }
+
+    /// Set the `value` at `path` in `key`.
+    pub fn set(mut self, key: K, path: P, value: &'a T) -> Self {
+        self.push(key, path, value);
+        self
+    }
+
+    /// Set the `value` at `path` in `key`, without moving the builder.
+    pub fn push(&mut self, key: K, path: P, value: &'a T) {
+        self.triples.push((key, path, value));
+    }
+
+    /// The number of values to set.
+    pub fn len(&self) -> usize {
+        self.triples.len()
+    }
+
+    /// Return true if there are no values to set.
+    pub fn is_empty(&self) -> bool {
+        self.triples.is_empty()
+    }
+}
+
+impl<K, P, T> JsonMSet<'_, K, P, T>
+where
+    K: ToSingleRedisArg,
+    P: ToSingleRedisArg,
+    T: Serialize + ?Sized,
+{
+    /// Create the `JSON.MSET` command.
+    pub fn cmd(&self) -> ::redis::Cmd {
+        let mut cmd = ::redis::cmd("JSON.MSET");
+        cmd.arg(self);
+        cmd
+    }
+}
+
+impl<K, P, T: ?Sized> Default for JsonMSet<'_, K, P, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, K, P, T: ?Sized> FromIterator<(K, P, &'a T)> for JsonMSet<'a, K, P, T> {
+    fn from_iter<I: IntoIterator<Item = (K, P, &'a T)>>(iter: I) -> Self {
+        JsonMSet {
+            triples: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<K, P, T> ToRedisArgs for JsonMSet<'_, K, P, T>
+where
+    K: ToSingleRedisArg,
+    P: ToSingleRedisArg,
+    T: Serialize + ?Sized,
+{
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        for (key, path, value) in &self.triples {
+            key.write_redis_args(out);
+            path.write_redis_args(out);
+            JsonRef(*value).write_redis_args(out);
         }
-        let deser = serde_json::from_str(ch.as_str()).map_err(|e| {
-            format!("Response type in JSON could not be deserialized: {e} (response was {v:?})")
+    }
+
+    fn num_of_args(&self) -> usize {
+        self.triples.len() * 3
+    }
+}
+
+/// JsonLegacy is a wrapper to handle the return types from the RedisJSON commands with legacy paths.
+///
+/// Legacy paths (e.g. `.name` or `addresses[0]`, anything not starting with `$`) return only the first match,
+/// without the square brackets. `JsonLegacy` deserializes these bare values from JSON.
+///
+/// ```rust,no_run
+/// # use redis::{Client, JsonCommands, RedisResult};
+/// # use redis_macros::JsonLegacy;
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// # let _: () = con.json_set("user", "$", &r#"{ "id": 1 }"#)?;
+/// let JsonLegacy(user_id): JsonLegacy<u32> = con.json_get("user", ".id")?;  // => 1
+/// # Ok(())
+/// # }
+/// ```
+///
+/// If the path is only known at runtime, use [`from_json_reply`] to pick the right wrapper from the path syntax.
+///
+/// ***WARNING: `ToRedisArgs` panics if the underlying serialization fails.***
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct JsonLegacy<T>(
+    /// The inner type to deserialize
+    pub T,
+);
+
+impl<T> JsonLegacy<T> {
+    /// Return the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for JsonLegacy<T> {
+    fn from(value: T) -> Self {
+        JsonLegacy(value)
+    }
+}
+
+impl<T> Deref for JsonLegacy<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for JsonLegacy<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> ToRedisArgs for JsonLegacy<T>
+where
+    T: Serialize,
+{
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        JsonRef(&self.0).write_redis_args(out)
+    }
+}
+
+impl<T> ToSingleRedisArg for JsonLegacy<T> where T: Serialize {}
+
+impl<T> ::redis::FromRedisValue for JsonLegacy<T>
+where
+    T: DeserializeOwned,
+{
+    fn from_redis_value(v: Value) -> Result<JsonLegacy<T>, ParsingError> {
+        let target = ::std::any::type_name::<T>();
+        let error = |kind| DeserializeError::new(target, JSON_FORMAT, kind, &v);
+
+        let Value::BulkString(bytes) = &v else {
+            return Err(error(DeserializeErrorKind::UnexpectedValue).into());
+        };
+
+        let s = ::std::str::from_utf8(bytes)
+            .map_err(|e| error(DeserializeErrorKind::InvalidUtf8(e)))?;
+        let format = JSON_FORMAT;
+        let deser = __private::from_str(target, format, Redaction::None, &v, s, |s| {
+            SerdeJson::from_str(s)
         })?;
-        Ok(Json(deser))
+        Ok(JsonLegacy(deser))
+    }
+}
+
+/// Return true if the RedisJSON path uses the legacy syntax, which is every path not starting with `$`.
+///
+/// ```rust
+/// # use redis_macros::is_legacy_path;
+/// assert!(is_legacy_path(".addresses[0]"));
+/// assert!(!is_legacy_path("$.addresses[0]"));
+/// ```
+pub fn is_legacy_path(path: &str) -> bool {
+    !path.starts_with('$')
+}
+
+/// Deserialize the response of a RedisJSON command, depending on the syntax of the path it was called with.
+///
+/// JSONPath responses are deserialized like [`Json`] (requiring exactly one match), and legacy path responses
+/// like [`JsonLegacy`].
+///
+/// ```rust,no_run
+/// # use redis::{Client, JsonCommands, RedisResult, Value};
+/// # use redis_macros::from_json_reply;
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// # let path = ".id";
+/// let value: Value = con.json_get("user", path)?;
+/// let user_id: u32 = from_json_reply(path, value)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_json_reply<T>(path: &str, v: Value) -> Result<T, ParsingError>
+where
+    T: DeserializeOwned,
+{
+    if is_legacy_path(path) {
+        ::redis::FromRedisValue::from_redis_value(v).map(JsonLegacy::into_inner)
+    } else {
+        ::redis::FromRedisValue::from_redis_value(v).map(Json::into_inner)
     }
 }
@@ -0,0 +1,191 @@
+use crate::{JsonPath, JsonRef};
+use redis::{Pipeline, ToSingleRedisArg};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// JsonDiff is the minimal set of RedisJSON commands to update a stored document from an old value to a new one.
+///
+/// Instead of rewriting the whole document with `JSON.SET key $`, only the changed fields are written, so other
+/// fields modified by concurrent writers are not clobbered:
+///
+/// - a single changed field is written with `JSON.SET` (or removed with `JSON.DEL`),
+/// - multiple changed fields of the same object are written with one `JSON.MERGE`,
+/// - fields set to `null`, or to objects with `null` fields, are written with `JSON.SET` (a merge would remove them),
+/// - changed objects are compared recursively, as well as arrays with the same length (otherwise they are replaced).
+///
+/// ```rust,no_run
+/// # use redis::{Client, RedisResult};
+/// use redis_macros::{JsonDiff, JsonOp};
+/// use serde::Serialize;
+///
+/// #[derive(Clone, Serialize)]
+/// struct User { id: u32, name: String, tags: Vec<String> }
+///
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// let user = User { id: 1, name: "Ziggy".to_string(), tags: vec![] };
+/// let mut modified = user.clone();
+/// modified.name = "Bowie".to_string();
+///
+/// let diff = JsonDiff::new(&user, &modified)?;
+/// assert_eq!(diff.ops(), &[JsonOp::Set("$.name".to_string(), "Bowie".into())]);
+/// let _: () = diff.pipeline("user").query(&mut con)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JsonDiff {
+    ops: Vec<JsonOp>,
+}
+
+/// A RedisJSON command of a [`JsonDiff`], with the JSONPath and the value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonOp {
+    /// Replace the value at the path with `JSON.SET`.
+    Set(String, Value),
+    /// Remove the value at the path with `JSON.DEL`.
+    Del(String),
+    /// Merge the object into the object at the path with `JSON.MERGE` (`null` values remove the fields).
+    Merge(String, Value),
+}
+
+impl JsonDiff {
+    /// Compare the serialized `old` and `new` values.
+    pub fn new<T>(old: &T, new: &T) -> Result<Self, serde_json::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(JsonDiff::from_values(
+            &serde_json::to_value(old)?,
+            &serde_json::to_value(new)?,
+        ))
+    }
+
+    /// Compare the `old` and `new` JSON values.
+    pub fn from_values(old: &Value, new: &Value) -> Self {
+        let mut ops = Vec::new();
+        diff(&JsonPath::root(), old, new, &mut ops);
+        JsonDiff { ops }
+    }
+
+    /// The commands to run, in order.
+    pub fn ops(&self) -> &[JsonOp] {
+        &self.ops
+    }
+
+    /// Return true if the values are the same.
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Create a pipeline with the commands for `key`. The replies of the commands are ignored.
+    ///
+    /// The pipeline is not atomic by default, use [`Pipeline::atomic`] to run it in a transaction.
+    pub fn pipeline<K: ToSingleRedisArg>(&self, key: K) -> Pipeline {
+        let mut pipe = redis::pipe();
+        for op in &self.ops {
+            let cmd = match op {
+                JsonOp::Set(path, value) => redis::cmd("JSON.SET")
+                    .arg(&key)
+                    .arg(path)
+                    .arg(JsonRef(value))
+                    .take(),
+                JsonOp::Del(path) => redis::cmd("JSON.DEL").arg(&key).arg(path).take(),
+                JsonOp::Merge(path, value) => redis::cmd("JSON.MERGE")
+                    .arg(&key)
+                    .arg(path)
+                    .arg(JsonRef(value))
+                    .take(),
+            };
+            pipe.add_command(cmd).ignore();
+        }
+        pipe
+    }
+}
+
+fn diff(path: &JsonPath<Value>, old: &Value, new: &Value, ops: &mut Vec<JsonOp>) {
+    match (old, new) {
+        _ if old == new => {}
+        (Value::Object(old), Value::Object(new)) => diff_objects(path, old, new, ops),
+        (Value::Array(old), Value::Array(new)) if old.len() == new.len() => {
+            for (index, (old, new)) in old.iter().zip(new).enumerate() {
+                let path = JsonPath::new_unchecked(format!("{path}[{index}]"));
+                diff(&path, old, new, ops);
+            }
+        }
+        _ => ops.push(JsonOp::Set(path.to_string(), new.clone())),
+    }
+}
+
+fn diff_objects(
+    path: &JsonPath<Value>,
+    old: &Map<String, Value>,
+    new: &Map<String, Value>,
+    ops: &mut Vec<JsonOp>,
+) {
+    // The fields that can be written together with a merge patch
+    let mut patch = Map::new();
+    let mut nested = Vec::new();
+    let mut nulls = Vec::new();
+    for (name, old_value) in old {
+        if !new.contains_key(name) {
+            patch.insert(name.clone(), Value::Null);
+        } else if old_value != &new[name] && is_nested(old_value, &new[name]) {
+            nested.push(name);
+        }
+    }
+    for (name, new_value) in new {
+        match old.get(name) {
+            Some(old_value) if old_value == new_value => {}
+            Some(old_value) if is_nested(old_value, new_value) => {}
+            // Merge patches cannot set fields to null (nor the fields of nested objects), these are set separately
+            _ if new_value.is_null() || has_null_member(new_value) => nulls.push(name),
+            _ => {
+                patch.insert(name.clone(), new_value.clone());
+            }
+        }
+    }
+
+    match patch.len() {
+        0 => {}
+        1 => {
+            let (name, value) = patch.into_iter().next().unwrap();
+            let path = path.member_unchecked::<Value>(&name).into_string();
+            if value.is_null() {
+                ops.push(JsonOp::Del(path));
+            } else {
+                ops.push(JsonOp::Set(path, value));
+            }
+        }
+        _ => ops.push(JsonOp::Merge(path.to_string(), Value::Object(patch))),
+    }
+    for name in nulls {
+        ops.push(JsonOp::Set(
+            path.member_unchecked::<Value>(name).into_string(),
+            new[name].clone(),
+        ));
+    }
+    for name in nested {
+        diff(&path.member_unchecked(name), &old[name], &new[name], ops);
+    }
+}
+
+/// Return true if the values should be compared recursively, instead of replacing them.
+///
+/// Merging objects would keep the removed fields, so they are always compared separately.
+fn is_nested(old: &Value, new: &Value) -> bool {
+    match (old, new) {
+        (Value::Object(_), Value::Object(_)) => true,
+        (Value::Array(old), Value::Array(new)) => old.len() == new.len(),
+        _ => false,
+    }
+}
+
+/// Return true if the object, or one of its nested objects, has null fields (which a merge patch would remove).
+fn has_null_member(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => map.values().any(|v| v.is_null() || has_null_member(v)),
+        _ => false,
+    }
+}
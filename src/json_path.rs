@@ -0,0 +1,444 @@
+use crate::Json;
+use redis::{
+    Cmd, ConnectionLike, FromRedisValue, RedisResult, RedisWrite, ToRedisArgs, ToSingleRedisArg,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, VecDeque},
+    fmt,
+    marker::PhantomData,
+};
+
+/// JsonPath is a JSONPath that knows the type of the value it points to.
+///
+/// Deriving [`RedisJsonPath`](crate::RedisJsonPath) generates the accessors for the fields of a struct, starting from
+/// `User::path()`. Because the accessors are generated from the fields, renaming a field (or its `#[serde(rename)]`)
+/// breaks at compile time, instead of silently querying the wrong path.
+///
+/// ```rust
+/// use redis_macros::{JsonPath, RedisJsonPath};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, RedisJsonPath)]
+/// struct Address { street: String }
+///
+/// #[derive(Serialize, Deserialize, RedisJsonPath)]
+/// struct User {
+///     #[serde(rename = "fullName")]
+///     name: String,
+///     addresses: Vec<Address>,
+/// }
+///
+/// let path: JsonPath<String> = User::path().addresses().index(0).street();
+/// assert_eq!(path.as_str(), "$.addresses[0].street");
+/// assert_eq!(User::path().name().as_str(), "$.fullName");
+/// ```
+///
+/// It can be passed to any command as a path, and to the commands of [`TypedJsonCommands`] to check the type
+/// of the value.
+pub struct JsonPath<T: ?Sized> {
+    path: String,
+    target: PhantomData<fn() -> T>,
+}
+
+impl<T: ?Sized> JsonPath<T> {
+    /// The root of the document (`$`).
+    pub fn root() -> Self {
+        JsonPath::new("$".to_string())
+    }
+
+    /// Create a path from a string, without checking that it points to a `T`.
+    pub fn new_unchecked(path: impl Into<String>) -> Self {
+        JsonPath::new(path.into())
+    }
+
+    fn new(path: String) -> Self {
+        JsonPath {
+            path,
+            target: PhantomData,
+        }
+    }
+
+    /// The JSONPath as a string.
+    pub fn as_str(&self) -> &str {
+        &self.path
+    }
+
+    /// Return the JSONPath as a string.
+    pub fn into_string(self) -> String {
+        self.path
+    }
+
+    /// The path of the member `name` of the object, without checking that it points to a `U`.
+    ///
+    /// Names that are not identifiers are written in bracket notation (e.g. `$["first name"]`).
+    pub fn member_unchecked<U: ?Sized>(&self, name: &str) -> JsonPath<U> {
+        let mut chars = name.chars();
+        let is_identifier = chars
+            .next()
+            .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+            && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+        if is_identifier {
+            JsonPath::new(format!("{}.{name}", self.path))
+        } else {
+            let name = serde_json::to_string(name).unwrap();
+            JsonPath::new(format!("{}[{name}]", self.path))
+        }
+    }
+
+    /// The same path, pointing to a different type.
+    pub fn cast_unchecked<U: ?Sized>(self) -> JsonPath<U> {
+        JsonPath::new(self.path)
+    }
+}
+
+impl<T: HasJsonPath> JsonPath<Vec<T>> {
+    /// The path of the element at `index` of the array.
+    pub fn index(&self, index: usize) -> T::Path {
+        T::json_path(JsonPath::new(format!("{}[{index}]", self.path)))
+    }
+}
+
+impl<T: HasJsonPath> JsonPath<VecDeque<T>> {
+    /// The path of the element at `index` of the array.
+    pub fn index(&self, index: usize) -> T::Path {
+        T::json_path(JsonPath::new(format!("{}[{index}]", self.path)))
+    }
+}
+
+impl<V: HasJsonPath, S> JsonPath<HashMap<String, V, S>> {
+    /// The path of the value at `key` of the object.
+    pub fn key(&self, key: &str) -> V::Path {
+        V::json_path(self.member_unchecked(key))
+    }
+}
+
+impl<V: HasJsonPath> JsonPath<BTreeMap<String, V>> {
+    /// The path of the value at `key` of the object.
+    pub fn key(&self, key: &str) -> V::Path {
+        V::json_path(self.member_unchecked(key))
+    }
+}
+
+impl<T: ?Sized> Clone for JsonPath<T> {
+    fn clone(&self) -> Self {
+        JsonPath::new(self.path.clone())
+    }
+}
+
+impl<T: ?Sized> PartialEq for JsonPath<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+    }
+}
+
+impl<T: ?Sized> Eq for JsonPath<T> {}
+
+impl<T: ?Sized> fmt::Debug for JsonPath<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("JsonPath").field(&self.path).finish()
+    }
+}
+
+impl<T: ?Sized> fmt::Display for JsonPath<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+impl<T: ?Sized> AsRef<str> for JsonPath<T> {
+    fn as_ref(&self) -> &str {
+        &self.path
+    }
+}
+
+impl<T: ?Sized> ToRedisArgs for JsonPath<T> {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        out.write_arg(self.path.as_bytes())
+    }
+}
+
+impl<T: ?Sized> ToSingleRedisArg for JsonPath<T> {}
+
+/// Types that can be the target of a [`JsonPath`], with the accessors of their paths.
+///
+/// It is implemented by [`RedisJsonPath`](crate::RedisJsonPath) for structs (with generated accessors) and enums,
+/// and for the common standard types. Fields of other types can be marked with `#[redis_path(leaf)]`.
+pub trait HasJsonPath {
+    /// The path type with the accessors, usually [`JsonPath<Self>`].
+    type Path: TypedJsonPath;
+
+    /// Wrap the path pointing to this type.
+    fn json_path(path: JsonPath<Self>) -> Self::Path;
+}
+
+macro_rules! impl_leaf {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl HasJsonPath for $ty {
+                type Path = JsonPath<$ty>;
+
+                fn json_path(path: JsonPath<Self>) -> Self::Path {
+                    path
+                }
+            }
+        )*
+    };
+}
+
+impl_leaf!(
+    bool,
+    char,
+    String,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    f32,
+    f64,
+    serde_json::Value,
+);
+
+impl<T: HasJsonPath> HasJsonPath for Vec<T> {
+    type Path = JsonPath<Vec<T>>;
+
+    fn json_path(path: JsonPath<Self>) -> Self::Path {
+        path
+    }
+}
+
+impl<T: HasJsonPath> HasJsonPath for VecDeque<T> {
+    type Path = JsonPath<VecDeque<T>>;
+
+    fn json_path(path: JsonPath<Self>) -> Self::Path {
+        path
+    }
+}
+
+impl<V: HasJsonPath, S> HasJsonPath for HashMap<String, V, S> {
+    type Path = JsonPath<HashMap<String, V, S>>;
+
+    fn json_path(path: JsonPath<Self>) -> Self::Path {
+        path
+    }
+}
+
+impl<V: HasJsonPath> HasJsonPath for BTreeMap<String, V> {
+    type Path = JsonPath<BTreeMap<String, V>>;
+
+    fn json_path(path: JsonPath<Self>) -> Self::Path {
+        path
+    }
+}
+
+// Optional and boxed values are in the same place in the document as the inner value
+impl<T: HasJsonPath> HasJsonPath for Option<T> {
+    type Path = T::Path;
+
+    fn json_path(path: JsonPath<Self>) -> Self::Path {
+        T::json_path(path.cast_unchecked())
+    }
+}
+
+impl<T: HasJsonPath> HasJsonPath for Box<T> {
+    type Path = T::Path;
+
+    fn json_path(path: JsonPath<Self>) -> Self::Path {
+        T::json_path(path.cast_unchecked())
+    }
+}
+
+// Borrowed values are read back as the owned values
+impl<T> HasJsonPath for Cow<'_, T>
+where
+    T: ?Sized + ToOwned,
+    T::Owned: HasJsonPath,
+{
+    type Path = <T::Owned as HasJsonPath>::Path;
+
+    fn json_path(path: JsonPath<Self>) -> Self::Path {
+        T::Owned::json_path(path.cast_unchecked())
+    }
+}
+
+// Lazy values have the same JSON as the decoded values
+impl<T> HasJsonPath for crate::Lazy<T>
+where
+    T: HasJsonPath,
+{
+    type Path = T::Path;
+
+    fn json_path(path: JsonPath<Self>) -> Self::Path {
+        T::json_path(path.cast_unchecked())
+    }
+}
+
+/// A JSONPath argument with the type of the value it points to.
+///
+/// It is implemented by [`JsonPath`] and by the path types generated by [`RedisJsonPath`](crate::RedisJsonPath).
+pub trait TypedJsonPath: ToSingleRedisArg {
+    /// The type of the value at the path.
+    type Target;
+}
+
+impl<T> TypedJsonPath for JsonPath<T> {
+    type Target = T;
+}
+
+impl<P: TypedJsonPath> TypedJsonPath for &P {
+    type Target = P::Target;
+}
+
+/// RedisJSON commands that check the type of the values with [`JsonPath`]s.
+///
+/// The paths must match exactly one value (like [`Json`]).
+///
+/// ```rust,no_run
+/// # use redis::{Client, RedisResult};
+/// use redis_macros::{RedisJsonPath, TypedJsonCommands};
+/// # use serde::{Deserialize, Serialize};
+/// # #[derive(Serialize, Deserialize, RedisJsonPath)]
+/// # struct Address { street: String }
+/// # #[derive(Serialize, Deserialize, RedisJsonPath)]
+/// # struct User { name: String, addresses: Vec<Address> }
+///
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// let name: String = con.json_get_typed("user", User::path().name())?;
+/// let address = Address { street: "Downing".to_string() };
+/// let _: () = con.json_arr_append_typed("user", User::path().addresses(), &address)?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait TypedJsonCommands: ConnectionLike + Sized {
+    /// Get the value at `path` in `key` with `JSON.GET`.
+    fn json_get_typed<K, P>(&mut self, key: K, path: P) -> RedisResult<P::Target>
+    where
+        K: ToSingleRedisArg,
+        P: TypedJsonPath,
+        P::Target: DeserializeOwned,
+    {
+        let Json(value): Json<_> = json_get_cmd(key, path).query(self)?;
+        Ok(value)
+    }
+
+    /// Set the `value` at `path` in `key` with `JSON.SET`.
+    fn json_set_typed<K, P, RV>(&mut self, key: K, path: P, value: &P::Target) -> RedisResult<RV>
+    where
+        K: ToSingleRedisArg,
+        P: TypedJsonPath,
+        P::Target: Serialize,
+        RV: FromRedisValue,
+    {
+        json_set_cmd(key, path, value).query(self)
+    }
+
+    /// Append the `value` to the array at `path` in `key` with `JSON.ARRAPPEND`.
+    fn json_arr_append_typed<K, P, V, RV>(&mut self, key: K, path: P, value: &V) -> RedisResult<RV>
+    where
+        K: ToSingleRedisArg,
+        P: TypedJsonPath<Target = Vec<V>>,
+        V: Serialize,
+        RV: FromRedisValue,
+    {
+        json_arr_append_cmd(key, path, value).query(self)
+    }
+}
+
+impl<C: ConnectionLike> TypedJsonCommands for C {}
+
+/// Async version of [`TypedJsonCommands`], enabled with the `aio` feature.
+#[cfg(feature = "aio")]
+pub trait TypedJsonAsyncCommands: redis::aio::ConnectionLike + Send + Sized {
+    /// Get the value at `path` in `key` with `JSON.GET`.
+    fn json_get_typed<'a, K, P>(&'a mut self, key: K, path: P) -> redis::RedisFuture<'a, P::Target>
+    where
+        K: ToSingleRedisArg,
+        P: TypedJsonPath,
+        P::Target: DeserializeOwned + Send,
+    {
+        let cmd = json_get_cmd(key, path);
+        Box::pin(async move {
+            let Json(value): Json<_> = cmd.query_async(self).await?;
+            Ok(value)
+        })
+    }
+
+    /// Set the `value` at `path` in `key` with `JSON.SET`.
+    fn json_set_typed<'a, K, P, RV>(
+        &'a mut self,
+        key: K,
+        path: P,
+        value: &P::Target,
+    ) -> redis::RedisFuture<'a, RV>
+    where
+        K: ToSingleRedisArg,
+        P: TypedJsonPath,
+        P::Target: Serialize,
+        RV: FromRedisValue + Send,
+    {
+        let cmd = json_set_cmd(key, path, value);
+        Box::pin(async move { cmd.query_async(self).await })
+    }
+
+    /// Append the `value` to the array at `path` in `key` with `JSON.ARRAPPEND`.
+    fn json_arr_append_typed<'a, K, P, V, RV>(
+        &'a mut self,
+        key: K,
+        path: P,
+        value: &V,
+    ) -> redis::RedisFuture<'a, RV>
+    where
+        K: ToSingleRedisArg,
+        P: TypedJsonPath<Target = Vec<V>>,
+        V: Serialize,
+        RV: FromRedisValue + Send,
+    {
+        let cmd = json_arr_append_cmd(key, path, value);
+        Box::pin(async move { cmd.query_async(self).await })
+    }
+}
+
+#[cfg(feature = "aio")]
+impl<C: redis::aio::ConnectionLike + Send> TypedJsonAsyncCommands for C {}
+
+fn json_get_cmd<K: ToSingleRedisArg, P: TypedJsonPath>(key: K, path: P) -> Cmd {
+    let mut cmd = redis::cmd("JSON.GET");
+    cmd.arg(key).arg(path);
+    cmd
+}
+
+fn json_set_cmd<K, P, V>(key: K, path: P, value: &V) -> Cmd
+where
+    K: ToSingleRedisArg,
+    P: TypedJsonPath,
+    V: Serialize + ?Sized,
+{
+    let mut cmd = redis::cmd("JSON.SET");
+    cmd.arg(key).arg(path).arg(crate::JsonRef(value));
+    cmd
+}
+
+fn json_arr_append_cmd<K, P, V>(key: K, path: P, value: &V) -> Cmd
+where
+    K: ToSingleRedisArg,
+    P: TypedJsonPath,
+    V: Serialize,
+{
+    let mut cmd = redis::cmd("JSON.ARRAPPEND");
+    cmd.arg(key).arg(path).arg(crate::JsonRef(value));
+    cmd
+}
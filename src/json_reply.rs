@@ -0,0 +1,242 @@
+use crate::{
+    __private, engine::JSON_FORMAT, DeserializeError, DeserializeErrorKind, JsonEngine, Redaction,
+    SerdeJson,
+};
+use redis::{FromRedisValue, ParsingError, Value};
+use serde::de::DeserializeOwned;
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+/// PerMatch is a wrapper for the replies of RedisJSON commands that return a value for every match of the JSONPath.
+///
+/// Commands like `JSON.ARRLEN`, `JSON.STRLEN`, `JSON.OBJKEYS`, `JSON.ARRPOP` or `JSON.TYPE` return an array with
+/// a value for each match, and nil for the matches with a different type (e.g. `JSON.STRLEN` on a number).
+/// With legacy paths, they return the value of the first match only: decode it without `PerMatch` (although replies
+/// that are not arrays are decoded as a single match).
+///
+/// ```rust,no_run
+/// # use redis::{Client, JsonCommands, RedisResult};
+/// # use redis_macros::PerMatch;
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// # let _: () = con.json_set("user", "$", &r#"{ "name": "Ziggy", "id": 1 }"#)?;
+/// let PerMatch(lengths): PerMatch<Option<usize>> = con.json_str_len("user", "$.*")?;  // => vec![Some(5), None]
+/// let PerMatch(keys): PerMatch<Option<Vec<String>>> = con.json_obj_keys("user", "$")?;  // => vec![Some(vec!["name", "id"])]
+/// # Ok(())
+/// # }
+/// ```
+///
+/// `JSON.NUMINCRBY` returns the new values as a JSON array instead, use [`JsonAll<Option<T>>`](crate::JsonAll) for it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PerMatch<T>(
+    /// The values for every match
+    pub Vec<T>,
+);
+
+impl<T> PerMatch<T> {
+    /// Return the values.
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> From<Vec<T>> for PerMatch<T> {
+    fn from(value: Vec<T>) -> Self {
+        PerMatch(value)
+    }
+}
+
+impl<T> Deref for PerMatch<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for PerMatch<T> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        &mut self.0
+    }
+}
+
+impl<T> IntoIterator for PerMatch<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<T> FromRedisValue for PerMatch<T>
+where
+    T: FromRedisValue,
+{
+    fn from_redis_value(v: Value) -> Result<PerMatch<T>, ParsingError> {
+        match v {
+            Value::Array(values) => values
+                .into_iter()
+                .map(T::from_redis_value)
+                .collect::<Result<_, _>>()
+                .map(PerMatch),
+            // Legacy paths only return the value of the first match
+            v => Ok(PerMatch(vec![T::from_redis_value(v)?])),
+        }
+    }
+}
+
+/// JsonType is the type of a JSON value, returned by `JSON.TYPE`.
+///
+/// ```rust,no_run
+/// # use redis::{Client, JsonCommands, RedisResult};
+/// # use redis_macros::{JsonType, PerMatch};
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// # let _: () = con.json_set("user", "$", &r#"{ "id": 1 }"#)?;
+/// let PerMatch(types): PerMatch<JsonType> = con.json_type("user", "$.id")?;  // => vec![JsonType::Integer]
+/// let id_type: JsonType = con.json_type("user", ".id")?;  // => JsonType::Integer
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JsonType {
+    /// `null`
+    Null,
+    /// `true` or `false`
+    Boolean,
+    /// A number without a fraction
+    Integer,
+    /// A number with a fraction
+    Number,
+    /// A string
+    String,
+    /// An object
+    Object,
+    /// An array
+    Array,
+}
+
+impl JsonType {
+    /// The name of the type, as returned by `JSON.TYPE`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JsonType::Null => "null",
+            JsonType::Boolean => "boolean",
+            JsonType::Integer => "integer",
+            JsonType::Number => "number",
+            JsonType::String => "string",
+            JsonType::Object => "object",
+            JsonType::Array => "array",
+        }
+    }
+
+    fn from_name(name: &[u8]) -> Option<JsonType> {
+        Some(match name {
+            b"null" => JsonType::Null,
+            b"boolean" => JsonType::Boolean,
+            b"integer" => JsonType::Integer,
+            b"number" => JsonType::Number,
+            b"string" => JsonType::String,
+            b"object" => JsonType::Object,
+            b"array" => JsonType::Array,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for JsonType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromRedisValue for JsonType {
+    fn from_redis_value(v: Value) -> Result<JsonType, ParsingError> {
+        let error = |kind| DeserializeError::new("JsonType", "JSON.TYPE", kind, &v).into();
+        let name = match &v {
+            Value::BulkString(bytes) => bytes.as_slice(),
+            Value::SimpleString(s) => s.as_bytes(),
+            _ => return Err(error(DeserializeErrorKind::UnexpectedValue)),
+        };
+        JsonType::from_name(name).ok_or_else(|| {
+            let names = [
+                JsonType::Null,
+                JsonType::Boolean,
+                JsonType::Integer,
+                JsonType::Number,
+                JsonType::String,
+                JsonType::Object,
+                JsonType::Array,
+            ]
+            .map(|t| t.as_str());
+            error(DeserializeErrorKind::unknown_variant(
+                String::from_utf8_lossy(name),
+                names,
+            ))
+        })
+    }
+}
+
+/// JsonPopped is the element removed by `JSON.ARRPOP`, or `None` if the array was empty (or not an array).
+///
+/// With JSONPaths, use it with [`PerMatch`] to get the popped element of every match.
+///
+/// ```rust,no_run
+/// # use redis::{Client, JsonCommands, RedisResult};
+/// # use redis_macros::{JsonPopped, PerMatch};
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// # let _: () = con.json_set("user", "$", &r#"{ "ids": [1, 2] }"#)?;
+/// let PerMatch(popped): PerMatch<JsonPopped<u32>> = con.json_arr_pop("user", "$.ids", -1)?;  // => vec![JsonPopped(Some(2))]
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JsonPopped<T>(
+    /// The removed element
+    pub Option<T>,
+);
+
+impl<T> JsonPopped<T> {
+    /// Return the removed element.
+    pub fn into_inner(self) -> Option<T> {
+        self.0
+    }
+}
+
+impl<T> Deref for JsonPopped<T> {
+    type Target = Option<T>;
+
+    fn deref(&self) -> &Option<T> {
+        &self.0
+    }
+}
+
+impl<T> FromRedisValue for JsonPopped<T>
+where
+    T: DeserializeOwned,
+{
+    fn from_redis_value(v: Value) -> Result<JsonPopped<T>, ParsingError> {
+        let target = ::std::any::type_name::<T>();
+        let format = JSON_FORMAT;
+        let error = |kind| DeserializeError::new(target, format, kind, &v);
+
+        let bytes = match &v {
+            Value::Nil => return Ok(JsonPopped(None)),
+            Value::BulkString(bytes) => bytes,
+            _ => return Err(error(DeserializeErrorKind::UnexpectedValue).into()),
+        };
+        let s = ::std::str::from_utf8(bytes)
+            .map_err(|e| error(DeserializeErrorKind::InvalidUtf8(e)))?;
+        let deser = __private::from_str(target, format, Redaction::None, &v, s, |s| {
+            SerdeJson::from_str(s)
+        })?;
+        Ok(JsonPopped(Some(deser)))
+    }
+}
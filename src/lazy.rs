@@ -0,0 +1,192 @@
+use crate::engine::{raw_json, RawJson, JSON_FORMAT};
+use crate::{__private, DeserializeError, DeserializeErrorKind, JsonEngine, Redaction, SerdeJson};
+use redis::Value;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::RawValue;
+use std::{fmt, sync::OnceLock};
+
+/// Lazy is a field type that keeps the raw JSON of a sub-document, and only deserializes it when it is accessed.
+///
+/// Large nested objects that most requests don't need can be kept as `Lazy`, so reading the document doesn't pay
+/// for deserializing them. The value is decoded on the first call to [`Lazy::get`], and cached afterwards.
+///
+/// ```rust
+/// use redis::{FromRedisValue, Value};
+/// use redis_macros::{FromRedisValue, Lazy, ToRedisArgs};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize, Serialize)]
+/// struct History { events: Vec<String> }
+///
+/// #[derive(Deserialize, Serialize, FromRedisValue, ToRedisArgs)]
+/// struct User {
+///     id: u32,
+///     history: Lazy<History>,
+/// }
+///
+/// let value = Value::BulkString(br#"{"id":1,"history":{"events":["login"]}}"#.to_vec());
+/// let user = User::from_redis_value(value).unwrap();  // => history is not deserialized
+/// assert_eq!(user.history.get().unwrap().events, vec!["login"]);
+/// ```
+///
+/// Until the value is changed with [`Lazy::get_mut`] or [`Lazy::set`], it is written back by the JSON engines (the
+/// derives and the `Json` wrappers) with the same bytes it was read from, without serializing it again. The raw JSON
+/// is written by `serde_json`: with the `simd-json` or `sonic-rs` features, the engines fall back to `serde_json`
+/// for these values, as they do to read them.
+///
+/// Other serializers (e.g. `serde_yaml`, or `serde_json` called directly) write the decoded value, so it is decoded first.
+///
+/// The errors of [`Lazy::get`] never include the raw JSON, because the redacted fields of the parent type are unknown.
+pub struct Lazy<T> {
+    // Either the raw JSON or the value is always set
+    raw: Option<Box<RawValue>>,
+    value: OnceLock<T>,
+}
+
+impl<T> Lazy<T> {
+    /// Create a decoded value, which is serialized when it is written.
+    pub fn new(value: T) -> Self {
+        Lazy {
+            raw: None,
+            value: OnceLock::from(value),
+        }
+    }
+
+    /// Return the raw JSON, if the value was read and hasn't been changed since.
+    pub fn raw_json(&self) -> Option<&str> {
+        self.raw.as_deref().map(RawValue::get)
+    }
+
+    /// Return true if the value has been decoded.
+    pub fn is_decoded(&self) -> bool {
+        self.value.get().is_some()
+    }
+
+    /// Replace the value, which is serialized when it is written.
+    pub fn set(&mut self, value: T) {
+        *self = Lazy::new(value);
+    }
+}
+
+impl<T> Lazy<T>
+where
+    T: DeserializeOwned,
+{
+    /// Return the value, deserializing it on the first access.
+    pub fn get(&self) -> Result<&T, DeserializeError> {
+        if let Some(value) = self.value.get() {
+            return Ok(value);
+        }
+        let value = self.decode()?;
+        Ok(self.value.get_or_init(|| value))
+    }
+
+    /// Return the value mutably, deserializing it on the first access. It is serialized again when it is written.
+    pub fn get_mut(&mut self) -> Result<&mut T, DeserializeError> {
+        if self.value.get().is_none() {
+            let value = self.decode()?;
+            self.value = OnceLock::from(value);
+        }
+        self.raw = None;
+        Ok(self.value.get_mut().unwrap())
+    }
+
+    /// Return the value, deserializing it if it hasn't been accessed.
+    pub fn into_inner(self) -> Result<T, DeserializeError> {
+        match self.value.into_inner() {
+            Some(value) => Ok(value),
+            None => Lazy {
+                raw: self.raw,
+                value: OnceLock::new(),
+            }
+            .decode(),
+        }
+    }
+
+    fn decode(&self) -> Result<T, DeserializeError> {
+        let target = ::std::any::type_name::<T>();
+        let Some(raw) = self.raw_json() else {
+            let kind = DeserializeErrorKind::UnexpectedValue;
+            return Err(DeserializeError::new(
+                target,
+                JSON_FORMAT,
+                kind,
+                &Value::Nil,
+            ));
+        };
+        // The redaction of the parent type is unknown here, so the sub-document is never shown in the errors
+        __private::from_str(
+            target,
+            JSON_FORMAT,
+            Redaction::Payload,
+            &Value::BulkString(raw.as_bytes().to_vec()),
+            raw,
+            SerdeJson::from_str,
+        )
+    }
+}
+
+impl<T> From<T> for Lazy<T> {
+    fn from(value: T) -> Self {
+        Lazy::new(value)
+    }
+}
+
+impl<T: Default> Default for Lazy<T> {
+    fn default() -> Self {
+        Lazy::new(T::default())
+    }
+}
+
+impl<T: Clone> Clone for Lazy<T> {
+    fn clone(&self) -> Self {
+        Lazy {
+            raw: self.raw.clone(),
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Lazy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.value.get(), &self.raw) {
+            (Some(value), _) => f.debug_tuple("Lazy").field(value).finish(),
+            (None, raw) => f.debug_tuple("Lazy").field(raw).finish(),
+        }
+    }
+}
+
+impl<T> Serialize for Lazy<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Write the unchanged values verbatim with the engines, only serde_json understands the raw JSON
+        match (&self.raw, raw_json()) {
+            (Some(raw), Some(RawJson::Verbatim)) => raw.serialize(serializer),
+            #[cfg(any(feature = "simd-json", feature = "sonic-rs"))]
+            (Some(_), Some(RawJson::Unsupported)) => Err(serde::ser::Error::custom(
+                "raw JSON is written with serde_json",
+            )),
+            _ => self
+                .get()
+                .map_err(serde::ser::Error::custom)?
+                .serialize(serializer),
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Lazy<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Lazy {
+            raw: Some(Box::<RawValue>::deserialize(deserializer)?),
+            value: OnceLock::new(),
+        })
+    }
+}
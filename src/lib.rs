@@ -175,11 +175,97 @@
 #[cfg(feature = "macros")]
 extern crate redis_macros_derive;
 
+#[cfg(any(feature = "json", feature = "macros"))]
+mod engine;
+
+#[cfg(any(feature = "json", feature = "macros"))]
+mod error;
+
+#[cfg(feature = "macros")]
+mod envelope;
+
+#[cfg(feature = "macros")]
+mod format;
+
+#[cfg(any(feature = "yaml", feature = "msgpack", feature = "cbor"))]
+mod formats;
+
+#[cfg(feature = "macros")]
+pub mod info;
+
 #[cfg(feature = "json")]
 mod json;
 
 #[cfg(feature = "json")]
-pub use json::Json;
+mod json_diff;
+
+#[cfg(feature = "json")]
+mod json_path;
+
+#[cfg(feature = "json")]
+mod json_reply;
+
+#[cfg(any(feature = "json", feature = "macros"))]
+mod lazy;
+
+#[cfg(feature = "macros")]
+mod validate;
+
+#[cfg(any(feature = "json", feature = "macros"))]
+pub use engine::{DefaultJsonEngine, JsonEngine, SerdeJson};
+
+#[cfg(all(any(feature = "json", feature = "macros"), feature = "simd-json"))]
+pub use engine::SimdJson;
+
+#[cfg(all(any(feature = "json", feature = "macros"), feature = "sonic-rs"))]
+pub use engine::SonicRs;
+
+#[cfg(any(feature = "json", feature = "macros"))]
+pub use error::{DeserializeError, DeserializeErrorKind, Redaction, PREVIEW_LIMIT, REDACTED};
+
+#[cfg(feature = "macros")]
+pub use envelope::Envelope;
+
+#[cfg(feature = "macros")]
+pub use format::{FromRedisValueWithFormat, WithFormat};
+
+#[cfg(feature = "yaml")]
+pub use formats::Yaml;
+
+#[cfg(feature = "msgpack")]
+pub use formats::MsgPack;
+
+#[cfg(feature = "cbor")]
+pub use formats::Cbor;
+
+#[cfg(feature = "json")]
+pub use json::{
+    from_json_reply, is_legacy_path, Json, JsonAll, JsonLegacy, JsonMSet, JsonMulti, JsonRef,
+    JsonWith,
+};
+
+#[cfg(feature = "json")]
+pub use json_diff::{JsonDiff, JsonOp};
+
+#[cfg(feature = "json")]
+pub use json_path::{HasJsonPath, JsonPath, TypedJsonCommands, TypedJsonPath};
+
+#[cfg(feature = "aio")]
+pub use json_path::TypedJsonAsyncCommands;
+
+#[cfg(feature = "json")]
+pub use json_reply::{JsonPopped, JsonType, PerMatch};
+
+#[cfg(any(feature = "json", feature = "macros"))]
+pub use lazy::Lazy;
+
+#[cfg(feature = "macros")]
+pub use validate::{ValidateArgs, ValidationError};
+
+#[cfg(any(feature = "json", feature = "macros"))]
+#[doc(hidden)]
+#[path = "private.rs"]
+pub mod __private;
 
 /// Derive macro for the redis crate's [`FromRedisValue`](../redis/trait.FromRedisValue.html) trait to allow parsing Redis responses to this type.
 ///
@@ -192,3 +278,21 @@ pub use redis_macros_derive::FromRedisValue;
 /// For more information see the `redis_macros_derive` crate: [`ToRedisArgs`](../redis_macros_derive/derive.FromRedisValue.html)
 #[cfg(feature = "macros")]
 pub use redis_macros_derive::ToRedisArgs;
+
+/// Derive macro for both `FromRedisValue` and `ToRedisArgs`, configured with a single `#[redis(...)]` attribute.
+///
+/// For more information see the `redis_macros_derive` crate: [`RedisValue`](../redis_macros_derive/derive.RedisValue.html)
+#[cfg(feature = "macros")]
+pub use redis_macros_derive::RedisValue;
+
+/// Derive macro to get only some JSONPaths of a RedisJSON document with a single `JSON.GET` command.
+///
+/// For more information see the `redis_macros_derive` crate: [`RedisJsonProjection`](../redis_macros_derive/derive.RedisJsonProjection.html)
+#[cfg(feature = "macros")]
+pub use redis_macros_derive::RedisJsonProjection;
+
+/// Derive macro to generate typed JSONPaths from the fields of a struct.
+///
+/// For more information see the `redis_macros_derive` crate: [`RedisJsonPath`](../redis_macros_derive/derive.RedisJsonPath.html)
+#[cfg(all(feature = "macros", feature = "json"))]
+pub use redis_macros_derive::RedisJsonPath;
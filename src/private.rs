@@ -0,0 +1,405 @@
+//! Helpers for the code generated by the derive macros. Not public API.
+
+use crate::engine::{JsonEngine, JSON_FORMAT};
+use crate::error::{DeserializeError, DeserializeErrorKind, Redaction, REDACTED};
+#[cfg(feature = "macros")]
+use crate::Envelope;
+pub use redis;
+use redis::Value;
+use serde::{
+    de::{DeserializeOwned, IgnoredAny, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+pub use serde_json;
+#[cfg(feature = "macros")]
+use std::borrow::Cow;
+use std::{cell::RefCell, fmt, fmt::Display, marker::PhantomData};
+
+thread_local! {
+    static FAILED_PATH: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Wrapper that records the path to the failing field while deserializing with any serializer.
+///
+/// The path is saved in a thread local, because `from_str` functions cannot pass state to the deserializer.
+pub struct Tracked<T>(T);
+
+impl<'de, T> Deserialize<'de> for Tracked<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut track = serde_path_to_error::Track::new();
+        T::deserialize(serde_path_to_error::Deserializer::new(
+            deserializer,
+            &mut track,
+        ))
+        .map(Tracked)
+        .inspect_err(|_| {
+            let path = track.path().to_string();
+            FAILED_PATH.with(|p| *p.borrow_mut() = (path != ".").then_some(path));
+        })
+    }
+}
+
+/// Deserialize the string `s` with the `from_str` function of the serializer, converting failures to [`DeserializeError`].
+pub fn from_str<T, E, F>(
+    target: &'static str,
+    format: &'static str,
+    redaction: Redaction,
+    value: &Value,
+    s: &str,
+    from_str: F,
+) -> Result<T, DeserializeError>
+where
+    F: FnOnce(&str) -> Result<Tracked<T>, E>,
+    E: Display,
+{
+    deserialize(target, format, redaction, value, s, from_str)
+}
+
+/// Deserialize the `bytes` with the `from_slice` function of a binary serializer, converting failures to [`DeserializeError`].
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+pub fn from_slice<T, E, F>(
+    target: &'static str,
+    format: &'static str,
+    redaction: Redaction,
+    value: &Value,
+    bytes: &[u8],
+    from_slice: F,
+) -> Result<T, DeserializeError>
+where
+    F: FnOnce(&[u8]) -> Result<Tracked<T>, E>,
+    E: Display,
+{
+    deserialize(target, format, redaction, value, bytes, from_slice)
+}
+
+fn deserialize<I, T, E, F>(
+    target: &'static str,
+    format: &'static str,
+    redaction: Redaction,
+    value: &Value,
+    input: &I,
+    deserialize: F,
+) -> Result<T, DeserializeError>
+where
+    I: ?Sized,
+    F: FnOnce(&I) -> Result<Tracked<T>, E>,
+    E: Display,
+{
+    FAILED_PATH.with(|p| p.borrow_mut().take());
+    deserialize(input).map(|Tracked(t)| t).map_err(|e| {
+        let path = FAILED_PATH.with(|p| p.borrow_mut().take());
+        let kind = DeserializeErrorKind::serde(e, path);
+        DeserializeError::with_redaction(target, format, kind, value, redaction)
+    })
+}
+
+/// Remove the envelope from the response, returning the format id and the payload.
+///
+/// Values without an envelope are returned as they are, without a format id. Compressed payloads are decompressed.
+#[cfg(feature = "macros")]
+pub fn open_envelope(bytes: &[u8]) -> Result<(Option<u8>, Cow<'_, [u8]>), DeserializeErrorKind> {
+    match Envelope::parse(bytes).map_err(DeserializeErrorKind::Envelope)? {
+        None => Ok((None, Cow::Borrowed(bytes))),
+        Some((envelope, payload)) if envelope.flags == Envelope::FLAG_COMPRESSED => {
+            Ok((Some(envelope.format), Cow::Owned(decompress(payload)?)))
+        }
+        Some((envelope, _)) if envelope.flags != 0 => Err(DeserializeErrorKind::Envelope(format!(
+            "unsupported flags {:#04b}",
+            envelope.flags
+        ))),
+        Some((envelope, payload)) => Ok((Some(envelope.format), Cow::Borrowed(payload))),
+    }
+}
+
+#[cfg(feature = "lz4")]
+fn decompress(payload: &[u8]) -> Result<Vec<u8>, DeserializeErrorKind> {
+    lz4_flex::decompress_size_prepended(payload)
+        .map_err(|e| DeserializeErrorKind::Envelope(format!("invalid compressed payload: {e}")))
+}
+
+#[cfg(all(feature = "macros", not(feature = "lz4")))]
+fn decompress(_: &[u8]) -> Result<Vec<u8>, DeserializeErrorKind> {
+    Err(DeserializeErrorKind::Envelope(
+        "compressed values require the `lz4` feature".to_string(),
+    ))
+}
+
+/// The compression of the values with `#[redis_compress]`, only implemented with the `lz4` feature.
+#[cfg(feature = "macros")]
+#[diagnostic::on_unimplemented(
+    message = "`#[redis_compress]` requires the `lz4` feature of redis-macros",
+    label = "the `lz4` feature of redis-macros is not enabled"
+)]
+pub trait Compress {
+    /// Compress the payload, to be written with [`Envelope::FLAG_COMPRESSED`].
+    fn compress(payload: &[u8]) -> Vec<u8>;
+}
+
+/// LZ4 block compression, with the size of the payload prepended.
+#[cfg(feature = "macros")]
+pub struct Lz4;
+
+#[cfg(feature = "lz4")]
+impl Compress for Lz4 {
+    fn compress(payload: &[u8]) -> Vec<u8> {
+        lz4_flex::compress_prepend_size(payload)
+    }
+}
+
+/// The `Value` of the redis crate used by redis_macros, the only one accepted by the helpers.
+#[cfg(feature = "macros")]
+#[diagnostic::on_unimplemented(
+    message = "`#[redis_crate]` must reexport the same version of redis as redis-macros",
+    label = "`{Self}` is not the `Value` of the redis crate of redis-macros",
+    note = "the derives pass the responses to redis-macros, so the redis crate of the path must be the version it depends on"
+)]
+pub trait RedisMacrosValue {
+    /// Return the response as the `Value` of redis_macros.
+    fn into_value(self) -> Value;
+}
+
+#[cfg(feature = "macros")]
+impl RedisMacrosValue for Value {
+    fn into_value(self) -> Value {
+        self
+    }
+}
+
+/// Convert the response of the crate of `#[redis_crate = "..."]`, failing to compile if it is another redis version.
+#[cfg(feature = "macros")]
+pub fn redis_macros_value<V: RedisMacrosValue>(value: V) -> Value {
+    value.into_value()
+}
+
+/// Return the string of the response, for the enums with `#[redis_enum(as_str)]`.
+#[cfg(feature = "macros")]
+pub fn variant_name<'a>(
+    target: &'static str,
+    format: &'static str,
+    value: &'a Value,
+) -> Result<&'a str, DeserializeError> {
+    let error = |kind| DeserializeError::new(target, format, kind, value);
+    match value {
+        Value::BulkString(bytes) => {
+            std::str::from_utf8(bytes).map_err(|e| error(DeserializeErrorKind::InvalidUtf8(e)))
+        }
+        Value::SimpleString(s) => Ok(s),
+        Value::VerbatimString { text, .. } => Ok(text),
+        _ => Err(error(DeserializeErrorKind::UnexpectedValue)),
+    }
+}
+
+/// Return the elements of the response, for the structs with `#[redis_reply(tuple)]`.
+#[cfg(feature = "macros")]
+pub fn reply_elements<'a>(
+    target: &'static str,
+    value: &'a Value,
+    len: usize,
+) -> Result<&'a [Value], DeserializeError> {
+    let kind = match value {
+        Value::Array(elements) if elements.len() == len => return Ok(elements),
+        Value::Array(elements) => DeserializeErrorKind::Arity {
+            expected: len,
+            actual: elements.len(),
+        },
+        _ => DeserializeErrorKind::NotArray,
+    };
+    Err(DeserializeError::new(
+        target,
+        "redis_reply(tuple)",
+        kind,
+        value,
+    ))
+}
+
+/// Convert the element of the response to the type of the field, for the structs with `#[redis_reply(tuple)]`.
+#[cfg(feature = "macros")]
+pub fn reply_field<T>(
+    target: &'static str,
+    field: &'static str,
+    element: &Value,
+    value: &Value,
+) -> Result<T, DeserializeError>
+where
+    T: redis::FromRedisValue,
+{
+    T::from_redis_value_ref(element).map_err(|e| {
+        let kind = DeserializeErrorKind::field(field, &e);
+        DeserializeError::new(target, "redis_reply(tuple)", kind, value)
+    })
+}
+
+/// The first element of a RedisJSON reply (a JSON array of the matches) and the number of matches.
+pub struct SingleMatch<T>(Option<T>, usize);
+
+impl<'de, T> Deserialize<'de> for SingleMatch<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SingleMatchVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> Visitor<'de> for SingleMatchVisitor<T>
+        where
+            T: Deserialize<'de>,
+        {
+            type Value = SingleMatch<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an array of RedisJSON matches")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let first = seq.next_element::<T>()?;
+                let mut count = usize::from(first.is_some());
+                while seq.next_element::<IgnoredAny>()?.is_some() {
+                    count += 1;
+                }
+                Ok(SingleMatch(first, count))
+            }
+        }
+
+        deserializer.deserialize_seq(SingleMatchVisitor(PhantomData))
+    }
+}
+
+/// Deserialize a RedisJSON reply with the JSON engine `E`, which must contain exactly one match.
+pub fn from_redis_json<T, E>(
+    target: &'static str,
+    redaction: Redaction,
+    value: &Value,
+    s: &str,
+) -> Result<T, DeserializeError>
+where
+    T: DeserializeOwned,
+    E: JsonEngine,
+{
+    from_redis_json_with(target, JSON_FORMAT, redaction, value, s, E::from_str)
+}
+
+/// Deserialize a RedisJSON reply with the `from_str` function of a JSON serializer, which must contain exactly one match.
+pub fn from_redis_json_with<T, E, F>(
+    target: &'static str,
+    format: &'static str,
+    redaction: Redaction,
+    value: &Value,
+    s: &str,
+    from_str_fn: F,
+) -> Result<T, DeserializeError>
+where
+    T: DeserializeOwned,
+    F: FnOnce(&str) -> Result<Tracked<SingleMatch<T>>, E>,
+    E: Display,
+{
+    let SingleMatch(first, count) = from_str(target, format, redaction, value, s, from_str_fn)?;
+    match first {
+        Some(first) if count == 1 => Ok(first),
+        _ => Err(DeserializeError::with_redaction(
+            target,
+            format,
+            DeserializeErrorKind::MatchCount(count),
+            value,
+            redaction,
+        )),
+    }
+}
+
+/// The matches of each JSONPath in a multi-path `JSON.GET` reply, used by `#[derive(RedisJsonProjection)]`.
+pub struct JsonProjection<'a> {
+    target: &'static str,
+    value: &'a Value,
+    redaction: Redaction,
+    matches: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Parse a `JSON.GET key path...` reply, which is an object of the matches keyed by the paths.
+///
+/// The reply is parsed with the `from_str` function of the JSON engine, and the redacted fields of the projection
+/// are named by their paths.
+pub fn json_projection<'a, E, F>(
+    target: &'static str,
+    value: &'a Value,
+    paths: &[&str],
+    redaction: Redaction,
+    from_str_fn: F,
+) -> Result<JsonProjection<'a>, DeserializeError>
+where
+    F: FnOnce(&str) -> Result<Tracked<serde_json::Value>, E>,
+    E: Display,
+{
+    let error =
+        |kind| DeserializeError::with_redaction(target, JSON_FORMAT, kind, value, redaction);
+    let Value::BulkString(bytes) = value else {
+        return Err(error(DeserializeErrorKind::UnexpectedValue));
+    };
+    let s = std::str::from_utf8(bytes).map_err(|e| error(DeserializeErrorKind::InvalidUtf8(e)))?;
+    let reply = from_str(target, JSON_FORMAT, redaction, value, s, from_str_fn)?;
+    let matches = match (paths, reply) {
+        // With a single path, RedisJSON returns the matches without the object around them
+        ([path], matches) => serde_json::Map::from_iter([(path.to_string(), matches)]),
+        (_, serde_json::Value::Object(matches)) => matches,
+        (_, other) => {
+            let message = serde_json::from_value::<serde_json::Map<_, _>>(other).unwrap_err();
+            return Err(error(DeserializeErrorKind::serde(message, None)));
+        }
+    };
+    Ok(JsonProjection {
+        target,
+        value,
+        redaction,
+        matches,
+    })
+}
+
+impl JsonProjection<'_> {
+    /// Deserialize the single match of the JSONPath `path`.
+    pub fn get<T>(&self, path: &str) -> Result<T, DeserializeError>
+    where
+        T: DeserializeOwned,
+    {
+        let error = |message: &dyn Display, inner: Option<String>| {
+            // The inner path starts with the index of the match, which is always the first one
+            let inner = inner
+                .as_deref()
+                .map_or("", |inner| inner.strip_prefix("[0]").unwrap_or(inner));
+            let redacted = match self.redaction {
+                Redaction::Fields(paths) => paths.contains(&path),
+                _ => false,
+            };
+            let message: &dyn Display = if redacted { &REDACTED } else { message };
+            let kind = DeserializeErrorKind::serde(message, Some(format!("{path}{inner}")));
+            DeserializeError::with_redaction(
+                self.target,
+                JSON_FORMAT,
+                kind,
+                self.value,
+                self.redaction,
+            )
+        };
+
+        let Some(matches) = self.matches.get(path) else {
+            return Err(error(&"missing from the response", None));
+        };
+        FAILED_PATH.with(|p| p.borrow_mut().take());
+        match Tracked::<SingleMatch<T>>::deserialize(matches) {
+            Ok(Tracked(SingleMatch(Some(first), 1))) => Ok(first),
+            Ok(Tracked(SingleMatch(_, count))) => Err(error(
+                &format_args!("expected exactly one match, got {count}"),
+                None,
+            )),
+            Err(e) => Err(error(&e, FAILED_PATH.with(|p| p.borrow_mut().take()))),
+        }
+    }
+}
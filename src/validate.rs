@@ -0,0 +1,83 @@
+use redis::{ErrorKind, RedisError, ToRedisArgs};
+use std::fmt::{self, Display};
+
+/// Error returned when a value is rejected by its validator before writing it to Redis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    target: &'static str,
+    message: String,
+}
+
+impl ValidationError {
+    /// Create a new error for the `target` type with the message of the validator.
+    pub fn new(target: &'static str, message: impl Display) -> Self {
+        ValidationError {
+            target,
+            message: message.to_string(),
+        }
+    }
+
+    /// The name of the type that was validated.
+    pub fn target(&self) -> &'static str {
+        self.target
+    }
+
+    /// The message returned by the validator.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Validation of {} failed: {}", self.target, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl From<ValidationError> for RedisError {
+    fn from(err: ValidationError) -> RedisError {
+        RedisError::from((ErrorKind::Client, "Validation failed", err.to_string()))
+    }
+}
+
+/// Validate a value before passing it to Redis commands.
+///
+/// It is implemented by deriving [`ToRedisArgs`](crate::ToRedisArgs) with the `#[redis_validate]` attribute,
+/// which runs the same validators as when reading the value with [`FromRedisValue`](crate::FromRedisValue).
+///
+/// ```rust,no_run
+/// # use redis::{Client, Commands, RedisResult};
+/// use redis_macros::{ToRedisArgs, ValidateArgs};
+/// use serde::Serialize;
+///
+/// fn positive_balance(account: &Account) -> Result<(), String> {
+///     if account.balance < 0 {
+///         return Err(format!("balance is negative: {}", account.balance));
+///     }
+///     Ok(())
+/// }
+///
+/// #[derive(Serialize, ToRedisArgs)]
+/// #[redis_validate(positive_balance)]
+/// struct Account { balance: i64 }
+///
+/// # fn main () -> redis::RedisResult<()> {
+/// # let client = redis::Client::open("redis://localhost:6379/")?;
+/// # let mut con = client.get_connection()?;
+/// let account = Account { balance: -10 };
+/// // This fails before sending anything to Redis
+/// let _: () = con.set("account", account.validated()?)?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait ValidateArgs: ToRedisArgs {
+    /// Run the validators of the type.
+    fn validate_args(&self) -> Result<(), ValidationError>;
+
+    /// Run the validators, and return the value if it is valid.
+    fn validated(&self) -> Result<&Self, ValidationError> {
+        self.validate_args().map(|()| self)
+    }
+}
@@ -0,0 +1,8 @@
+#[test]
+pub fn it_should_report_malformed_attributes() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+    if !cfg!(feature = "lz4") {
+        t.compile_fail("tests/ui/without_lz4/*.rs");
+    }
+}
@@ -0,0 +1,100 @@
+#![cfg(feature = "lz4")]
+
+use redis::{FromRedisValue, ToRedisArgs, Value};
+use redis_macros::{Envelope, FromRedisValue, RedisValue, ToRedisArgs};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+#[redis_compress]
+struct User {
+    id: u32,
+    name: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, RedisValue)]
+#[redis(format = serde_yaml, compress, envelope(schema = 2))]
+struct Profile {
+    bio: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue)]
+struct PlainUser {
+    id: u32,
+    name: String,
+}
+
+#[test]
+pub fn it_should_compress_the_payload() {
+    let user = User {
+        id: 1,
+        name: "Ziggy".repeat(20),
+    };
+    let bytes = user.to_redis_args();
+    assert_eq!(
+        bytes[0][..Envelope::HEADER_LEN],
+        [
+            0xFE,
+            Envelope::FORMAT_JSON,
+            Envelope::FLAG_COMPRESSED,
+            0,
+            0,
+            0,
+            0
+        ]
+    );
+    assert!(bytes[0].len() < serde_json::to_string(&user).unwrap().len());
+
+    let result = User::from_redis_value(Value::BulkString(bytes[0].clone()));
+    assert_eq!(result, Ok(user));
+}
+
+#[test]
+pub fn it_should_compress_with_the_options_of_redis_value() {
+    let profile = Profile {
+        bio: "Hello".to_string(),
+    };
+    let bytes = profile.to_redis_args();
+    let (envelope, _) = Envelope::parse(&bytes[0]).unwrap().unwrap();
+    assert_eq!(
+        envelope,
+        Envelope::new(Envelope::FORMAT_YAML)
+            .with_flags(Envelope::FLAG_COMPRESSED)
+            .with_schema(2)
+    );
+
+    let result = Profile::from_redis_value(Value::BulkString(bytes[0].clone()));
+    assert_eq!(result, Ok(profile));
+}
+
+#[test]
+pub fn it_should_read_compressed_values_without_the_attribute() {
+    let user = User {
+        id: 1,
+        name: "Ziggy".to_string(),
+    };
+    let bytes = user.to_redis_args();
+    let result = PlainUser::from_redis_value(Value::BulkString(bytes[0].clone()));
+    assert_eq!(
+        result,
+        Ok(PlainUser {
+            id: 1,
+            name: "Ziggy".to_string(),
+        })
+    );
+}
+
+#[test]
+pub fn it_should_fail_if_the_compressed_payload_is_invalid() {
+    let val = Value::BulkString(
+        Envelope::new(Envelope::FORMAT_JSON)
+            .with_flags(Envelope::FLAG_COMPRESSED)
+            .write("{}".as_bytes()),
+    );
+    let result = User::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("invalid envelope: invalid compressed payload"),
+        "{err}"
+    );
+}
@@ -0,0 +1,219 @@
+use redis::{FromRedisValue, ToRedisArgs, Value};
+use redis_macros::{FromRedisValue, RedisJsonPath, RedisJsonProjection, ToRedisArgs};
+use serde::{Deserialize, Serialize};
+
+/// Reexports of the crates under other names, like `deadpool_redis::redis`
+mod reexports {
+    pub use redis as inner_redis;
+    pub use redis_macros as inner_macros;
+    pub use serde_json as inner_json;
+}
+
+/// The derives don't need the `redis` or `serde_json` names in scope
+mod renamed {
+    #[allow(unused_imports)]
+    use std::{self as redis, self as serde_json};
+
+    use redis_macros::{FromRedisValue, ToRedisArgs};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+    pub struct User {
+        pub id: u32,
+    }
+
+    #[derive(Debug, PartialEq, FromRedisValue, ToRedisArgs)]
+    #[redis_transparent]
+    pub struct UserId(pub u64);
+
+    #[derive(Debug, PartialEq, FromRedisValue, ToRedisArgs)]
+    #[redis_enum(as_str)]
+    pub enum Status {
+        Active,
+    }
+
+    #[derive(Debug, PartialEq, FromRedisValue)]
+    #[redis_reply(tuple)]
+    pub struct Scored {
+        pub member: String,
+        pub score: f64,
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+#[redis_crate = "crate::reexports::inner_redis"]
+#[serde_json_crate = "crate::reexports::inner_json"]
+struct Custom {
+    id: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+#[redis_macros_crate = "crate::reexports::inner_macros"]
+#[redis_envelope(schema = 1)]
+#[redis_errors(redact_payload)]
+struct Wrapped {
+    id: u32,
+}
+
+#[derive(Debug, PartialEq, FromRedisValue, ToRedisArgs)]
+#[redis_macros_crate = "crate::reexports::inner_macros"]
+#[redis_enum(as_str)]
+enum WrappedStatus {
+    Active,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, RedisJsonPath, RedisJsonProjection)]
+#[redis_macros_crate = "crate::reexports::inner_macros"]
+struct WrappedDocument {
+    #[json_path("$.id")]
+    id: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+#[redis_crate = "deadpool_redis::redis"]
+struct Pooled {
+    id: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue)]
+#[serde_json_crate = "crate::reexports::inner_json"]
+#[redis_json(brackets = "required")]
+struct Required {
+    id: u32,
+}
+
+#[derive(Debug, PartialEq, FromRedisValue, ToRedisArgs)]
+#[redis_crate = "crate::reexports::inner_redis"]
+#[redis_transparent]
+struct Count(i64);
+
+#[derive(Debug, PartialEq, FromRedisValue, ToRedisArgs)]
+#[redis_crate = "crate::reexports::inner_redis"]
+#[redis_enum(repr = u8)]
+enum Level {
+    Low = 1,
+}
+
+#[derive(Debug, PartialEq, FromRedisValue)]
+#[redis_crate = "crate::reexports::inner_redis"]
+#[redis_reply(tuple)]
+struct Pair(String, i64);
+
+#[derive(Debug, PartialEq, RedisJsonProjection)]
+#[redis_crate = "crate::reexports::inner_redis"]
+struct Summary {
+    #[json_path("$.id")]
+    id: u32,
+}
+
+#[derive(Serialize, Deserialize, RedisJsonPath)]
+#[redis_crate = "crate::reexports::inner_redis"]
+struct Document {
+    id: u32,
+}
+
+#[test]
+pub fn it_should_not_need_the_crates_in_scope() {
+    let user = renamed::User { id: 1 };
+    let args = user.to_redis_args();
+    assert_eq!(args, vec![b"{\"id\":1}".to_vec()]);
+    let parsed = renamed::User::from_redis_value(Value::BulkString(args[0].clone())).unwrap();
+    assert_eq!(parsed, renamed::User { id: 1 });
+
+    assert_eq!(renamed::UserId(7).to_redis_args(), vec![b"7".to_vec()]);
+    assert_eq!(
+        renamed::Status::from_redis_value(Value::SimpleString("Active".to_string())),
+        Ok(renamed::Status::Active)
+    );
+    let scored = renamed::Scored::from_redis_value(Value::Array(vec![
+        Value::BulkString(b"a".to_vec()),
+        Value::Double(1.5),
+    ]))
+    .unwrap();
+    assert_eq!(scored.member, "a");
+}
+
+#[test]
+pub fn it_should_use_the_configured_crates() {
+    let custom = Custom { id: 1 };
+    let args = custom.to_redis_args();
+    assert_eq!(args, vec![b"{\"id\":1}".to_vec()]);
+    assert_eq!(
+        Custom::from_redis_value(Value::BulkString(b"[{\"id\":1}]".to_vec())),
+        Ok(Custom { id: 1 })
+    );
+    assert_eq!(Count::from_redis_value(Value::Int(3)).unwrap(), Count(3));
+    assert_eq!(Level::from_redis_value(Value::Int(1)), Ok(Level::Low));
+    let err = Level::from_redis_value(Value::Int(2)).unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to Level with redis_enum(repr = u8): unknown variant `2`, expected one of `1`. (response was int(2))");
+    assert_eq!(
+        Pair::from_redis_value(Value::Array(vec![
+            Value::BulkString(b"a".to_vec()),
+            Value::Int(1)
+        ])),
+        Ok(Pair("a".to_string(), 1))
+    );
+    assert_eq!(
+        Summary::from_redis_value(Value::BulkString(b"[1]".to_vec())),
+        Ok(Summary { id: 1 })
+    );
+    assert_eq!(
+        Document::path().id().to_redis_args(),
+        vec![b"$.id".to_vec()]
+    );
+}
+
+#[test]
+pub fn it_should_use_a_reexported_redis_crate() {
+    use deadpool_redis::redis::{FromRedisValue, ToRedisArgs, Value};
+
+    let args = Pooled { id: 1 }.to_redis_args();
+    assert_eq!(args, vec![b"{\"id\":1}".to_vec()]);
+    assert_eq!(
+        Pooled::from_redis_value(Value::BulkString(args[0].clone())),
+        Ok(Pooled { id: 1 })
+    );
+    let err = Pooled::from_redis_value(Value::Nil).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Incompatible type - Response type not deserializable to Pooled with serde_json: expected a bulk string. (response was nil)"
+    );
+}
+
+#[test]
+pub fn it_should_name_serde_json_in_errors_with_a_configured_crate() {
+    assert_eq!(
+        Required::from_redis_value(Value::BulkString(b"[{\"id\":1}]".to_vec())),
+        Ok(Required { id: 1 })
+    );
+    let err = Required::from_redis_value(Value::BulkString(b"[{\"id\":\"1\"}]".to_vec()))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("serde_json"), "{err}");
+    let err = Required::from_redis_value(Value::BulkString(b"[{\"id\":1},{\"id\":2}]".to_vec()))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("exactly one match, got 2"), "{err}");
+}
+
+#[test]
+pub fn it_should_use_the_configured_redis_macros_crate() {
+    let args = Wrapped { id: 1 }.to_redis_args();
+    assert_eq!(
+        Wrapped::from_redis_value(Value::BulkString(args[0].clone())),
+        Ok(Wrapped { id: 1 })
+    );
+    let err = Wrapped::from_redis_value(Value::BulkString(b"{\"id\":\"1\"}".to_vec()))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("<redacted>"), "{err}");
+    assert_eq!(
+        WrappedStatus::from_redis_value(Value::SimpleString("Active".to_string())),
+        Ok(WrappedStatus::Active)
+    );
+    assert_eq!(WrappedDocument::path().id().as_str(), "$.id");
+    assert_eq!(
+        WrappedDocument::from_redis_value(Value::BulkString(b"[1]".to_vec())),
+        Ok(WrappedDocument { id: 1 })
+    );
+}
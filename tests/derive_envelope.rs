@@ -0,0 +1,119 @@
+use redis::{FromRedisValue, ToRedisArgs, Value};
+use redis_macros::{Envelope, FromRedisValue, ToRedisArgs, WithFormat};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+#[redis_envelope(schema = 3)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+#[redis_serializer(serde_yaml)]
+#[redis_envelope]
+struct YamlUser {
+    id: u32,
+    name: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+#[redis_serializer(serde_json, fallback(serde_yaml))]
+struct MigratingUser {
+    id: u32,
+    name: String,
+}
+
+#[test]
+pub fn it_should_write_the_envelope() {
+    let user = User {
+        id: 1,
+        name: "Ziggy".to_string(),
+    };
+    let bytes = user.to_redis_args();
+    assert_eq!(
+        bytes[0],
+        [
+            &[0xFE, Envelope::FORMAT_JSON, 0, 0, 0, 0, 3],
+            "{\"id\":1,\"name\":\"Ziggy\"}".as_bytes()
+        ]
+        .concat()
+    );
+
+    let result = User::from_redis_value(Value::BulkString(bytes[0].clone()));
+    assert_eq!(result, Ok(user));
+}
+
+#[test]
+pub fn it_should_read_values_without_envelope() {
+    let val = Value::BulkString("{\"id\":1,\"name\":\"Ziggy\"}".as_bytes().into());
+    let result = User::from_redis_value(val);
+    assert_eq!(
+        result,
+        Ok(User {
+            id: 1,
+            name: "Ziggy".to_string(),
+        })
+    );
+}
+
+#[test]
+pub fn it_should_pick_the_serializer_by_the_format_id() {
+    let user = YamlUser {
+        id: 1,
+        name: "Ziggy".to_string(),
+    };
+    let bytes = user.to_redis_args();
+    assert_eq!(bytes[0][1], Envelope::FORMAT_YAML);
+
+    let result = WithFormat::<MigratingUser>::from_redis_value(Value::BulkString(bytes[0].clone()));
+    let WithFormat(parsed, format) = result.unwrap();
+    assert_eq!(
+        parsed,
+        MigratingUser {
+            id: 1,
+            name: "Ziggy".to_string(),
+        }
+    );
+    assert_eq!(format, "serde_yaml");
+}
+
+#[test]
+pub fn it_should_fail_if_the_format_is_not_supported() {
+    let val = Value::BulkString(
+        Envelope::new(Envelope::FORMAT_YAML).write("id: 1\nname: Ziggy\n".as_bytes()),
+    );
+    let result = User::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to User with serde_json: invalid envelope: unsupported format id 2. (response was binary-data([254, 2, 0, 0, 0, 0, 0, 105, 100, 58, 32, 49, 10, 110, 97, 109, 101, 58, 32, 90, 105, 103, 103, 121, 10]))".to_string());
+}
+
+#[test]
+pub fn it_should_fail_if_the_flags_are_not_supported() {
+    let val = Value::BulkString(
+        Envelope::new(Envelope::FORMAT_JSON)
+            .with_flags(Envelope::FLAG_ENCRYPTED)
+            .write("{}".as_bytes()),
+    );
+    let result = User::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to User with serde_json: invalid envelope: unsupported flags 0b10. (response was binary-data([254, 1, 2, 0, 0, 0, 0, 123, 125]))".to_string());
+
+    let val = Value::BulkString(vec![0xFE, 1]);
+    let result = User::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to User with serde_json: invalid envelope: envelope header is truncated (2 of 7 bytes). (response was binary-data([254, 1]))".to_string());
+}
+
+#[test]
+#[cfg(not(feature = "lz4"))]
+pub fn it_should_require_the_lz4_feature_for_compressed_values() {
+    let val = Value::BulkString(
+        Envelope::new(Envelope::FORMAT_JSON)
+            .with_flags(Envelope::FLAG_COMPRESSED)
+            .write("{}".as_bytes()),
+    );
+    let result = User::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to User with serde_json: invalid envelope: compressed values require the `lz4` feature. (response was binary-data([254, 1, 1, 0, 0, 0, 0, 123, 125]))".to_string());
+}
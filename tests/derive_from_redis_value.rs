@@ -1,5 +1,6 @@
 use redis::{FromRedisValue, Value};
 use redis_macros::FromRedisValue;
+use redis_macros::{DeserializeError, DeserializeErrorKind};
 use serde::Deserialize;
 
 #[derive(Debug, PartialEq, Deserialize)]
@@ -52,7 +53,7 @@ pub fn it_should_fail_if_input_is_not_compatible_with_type() {
     let val = Value::BulkString("{}".as_bytes().into());
     let result = User::from_redis_value(val);
     let err = result.unwrap_err();
-    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to User with serde_json. (response was bulk-string('\"{}\"'))".to_string());
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to User with serde_json: missing field `id` (line 1 column 2). (response was bulk-string('\"{}\"'))".to_string());
 }
 
 #[test]
@@ -60,7 +61,7 @@ pub fn it_should_fail_if_input_is_not_valid_utf8() {
     let val = Value::BulkString(vec![0, 159, 146, 150]); // Some invalid utf8
     let result = User::from_redis_value(val);
     let err = result.unwrap_err();
-    assert_eq!(err.to_string(), "Incompatible type - Response was not valid UTF-8 string. (response was binary-data([0, 159, 146, 150]))".to_string());
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to User with serde_json: invalid UTF-8: invalid utf-8 sequence of 1 bytes from index 1. (response was binary-data([0, 159, 146, 150]))".to_string());
 }
 
 #[test]
@@ -70,7 +71,50 @@ pub fn it_should_fail_if_input_is_missing() {
     let err = result.unwrap_err();
     assert_eq!(
         err.to_string(),
-        "Incompatible type - Response type was not deserializable to User. (response was nil)"
+        "Incompatible type - Response type not deserializable to User with serde_json: expected a bulk string. (response was nil)"
             .to_string()
     );
 }
+
+#[test]
+pub fn it_should_report_the_path_of_the_failing_field() {
+    let val = Value::BulkString(
+        "{\"id\":1,\"name\":\"Ziggy\",\"addresses\":[{\"Street\":\"Downing\"},{\"Road\":2}]}"
+            .as_bytes()
+            .into(),
+    );
+    let result = User::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to User with serde_json: invalid type: integer `2`, expected a string (at `addresses[1].Road`, line 1 column 66). (response was bulk-string('\"{\\\"id\\\":1,\\\"name\\\":\\\"Ziggy\\\",\\\"addresses\\\":[{\\\"Street\\\":\\\"Downing\\\"},{\\\"Road\\\":2}]}\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_truncate_large_responses_in_errors() {
+    let payload = format!("{{\"id\":\"{}\"}}", "a".repeat(10_000));
+    let val = Value::BulkString(payload.as_bytes().into());
+    let err = User::from_redis_value(val).unwrap_err().to_string();
+    assert!(err.len() < 1024);
+    assert!(err.ends_with("... (10009 bytes))"));
+}
+
+#[test]
+pub fn it_should_expose_the_structured_error() {
+    let val = Value::BulkString("{\"id\":\"1\"}".as_bytes().into());
+    let serde_err = serde_json::from_str::<User>("{\"id\":\"1\"}").unwrap_err();
+    let kind = DeserializeErrorKind::serde(serde_err, Some("id".to_string()));
+    let err = DeserializeError::new("User", "serde_json", kind, &val);
+    assert_eq!(err.target(), "User");
+    assert_eq!(err.format(), "serde_json");
+    assert_eq!(err.path(), Some("id"));
+    assert_eq!(err.location(), Some((1, 9)));
+    assert_eq!(err.preview(), "bulk-string('\"{\\\"id\\\":\\\"1\\\"}\"')");
+    assert_eq!(
+        err.kind(),
+        &DeserializeErrorKind::Serde {
+            message: "invalid type: string \"1\", expected u32".to_string(),
+            path: Some("id".to_string()),
+            line: Some(1),
+            column: Some(9),
+        }
+    );
+}
@@ -0,0 +1,130 @@
+use redis::{FromRedisValue, Value};
+use redis_macros::FromRedisValue;
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize, FromRedisValue)]
+#[redis_json(brackets = "auto")]
+struct AutoUser {
+    id: u32,
+}
+
+#[derive(Debug, PartialEq, Deserialize, FromRedisValue)]
+#[redis_json(brackets = "strict")]
+struct StrictUser {
+    id: u32,
+}
+
+#[derive(Debug, PartialEq, Deserialize, FromRedisValue)]
+#[redis_json(brackets = "required")]
+struct RequiredUser {
+    id: u32,
+}
+
+#[derive(Debug, PartialEq, Deserialize, FromRedisValue)]
+#[redis_json(brackets = "off")]
+struct OffUser {
+    id: u32,
+}
+
+fn bulk(s: &str) -> Value {
+    Value::BulkString(s.as_bytes().into())
+}
+
+#[test]
+pub fn it_should_accept_both_in_auto_mode() {
+    assert_eq!(
+        AutoUser::from_redis_value(bulk("{\"id\":1}")),
+        Ok(AutoUser { id: 1 })
+    );
+    assert_eq!(
+        AutoUser::from_redis_value(bulk("[{\"id\":1}]")),
+        Ok(AutoUser { id: 1 })
+    );
+}
+
+#[test]
+pub fn it_should_report_the_first_error_in_auto_mode_if_the_value_is_not_redis_json() {
+    let err = AutoUser::from_redis_value(bulk("{\"id\":\"1\"}")).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("invalid type: string \"1\", expected u32 (at `id`"),
+        "{err}"
+    );
+
+    // The retry would fail with another error on `"1",2`
+    let err = AutoUser::from_redis_value(bulk("[\"1\",2]")).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("invalid type: string \"1\", expected u32 (at `[0]`, line 1 column 4)"),
+        "{err}"
+    );
+
+    let err = AutoUser::from_redis_value(bulk("[{\"id\":\"1\"}]")).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("invalid type: string \"1\", expected u32 (at `id`"),
+        "{err}"
+    );
+}
+
+#[test]
+pub fn it_should_accept_both_in_strict_mode() {
+    assert_eq!(
+        StrictUser::from_redis_value(bulk("{\"id\":1}")),
+        Ok(StrictUser { id: 1 })
+    );
+    assert_eq!(
+        StrictUser::from_redis_value(bulk("[{\"id\":1}]")),
+        Ok(StrictUser { id: 1 })
+    );
+}
+
+#[test]
+pub fn it_should_fail_in_strict_mode_if_there_are_multiple_matches() {
+    let result = StrictUser::from_redis_value(bulk("[{\"id\":1},{\"id\":2}]"));
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to StrictUser with serde_json: expected exactly one match, got 2. (response was bulk-string('\"[{\\\"id\\\":1},{\\\"id\\\":2}]\"'))".to_string());
+
+    let result = StrictUser::from_redis_value(bulk("[]"));
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to StrictUser with serde_json: expected exactly one match, got 0. (response was bulk-string('\"[]\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_report_the_error_of_the_match_in_strict_mode() {
+    let result = StrictUser::from_redis_value(bulk("[{\"id\":\"1\"}]"));
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to StrictUser with serde_json: invalid type: string \"1\", expected u32 (at `[0].id`, line 1 column 10). (response was bulk-string('\"[{\\\"id\\\":\\\"1\\\"}]\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_require_brackets_in_required_mode() {
+    assert_eq!(
+        RequiredUser::from_redis_value(bulk("[{\"id\":1}]")),
+        Ok(RequiredUser { id: 1 })
+    );
+
+    let result = RequiredUser::from_redis_value(bulk("{\"id\":1}"));
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to RequiredUser with serde_json: expected a RedisJSON response wrapped in square brackets. (response was bulk-string('\"{\\\"id\\\":1}\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_reject_brackets_in_off_mode() {
+    assert_eq!(
+        OffUser::from_redis_value(bulk("{\"id\":1}")),
+        Ok(OffUser { id: 1 })
+    );
+
+    let result = OffUser::from_redis_value(bulk("[{\"id\":1}]"));
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to OffUser with serde_json: invalid type: sequence, expected struct OffUser. (response was bulk-string('\"[{\\\"id\\\":1}]\"'))".to_string());
+
+    // serde would read the single field from the sequence
+    let err = OffUser::from_redis_value(bulk("[1]")).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("invalid type: sequence, expected struct OffUser"),
+        "{err}"
+    );
+}
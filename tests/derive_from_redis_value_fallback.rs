@@ -0,0 +1,72 @@
+use redis::{FromRedisValue, ToRedisArgs, Value};
+use redis_macros::{FromRedisValue, ToRedisArgs, WithFormat};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Address {
+    Street(String),
+    Road(String),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+#[redis_serializer(serde_json, fallback(serde_yaml))]
+struct User {
+    id: u32,
+    name: String,
+    addresses: Vec<Address>,
+}
+
+fn user() -> User {
+    User {
+        id: 1,
+        name: "Ziggy".to_string(),
+        addresses: vec![
+            Address::Street("Downing".to_string()),
+            Address::Road("Abbey".to_string()),
+        ],
+    }
+}
+
+#[test]
+pub fn it_should_deserialize_with_the_primary_serializer() {
+    let val = Value::BulkString("{\"id\":1,\"name\":\"Ziggy\",\"addresses\":[{\"Street\":\"Downing\"},{\"Road\":\"Abbey\"}]}".as_bytes().into());
+    let result = WithFormat::<User>::from_redis_value(val);
+    let WithFormat(parsed, format) = result.unwrap();
+    assert_eq!(parsed, user());
+    assert_eq!(format, "serde_json");
+}
+
+#[test]
+pub fn it_should_deserialize_with_the_fallback_serializer() {
+    let val = Value::BulkString(
+        "id: 1
+name: Ziggy
+addresses:
+- !Street Downing
+- !Road Abbey
+"
+        .as_bytes()
+        .into(),
+    );
+    let result = WithFormat::<User>::from_redis_value(val.clone());
+    let WithFormat(parsed, format) = result.unwrap();
+    assert_eq!(parsed, user());
+    assert_eq!(format, "serde_yaml");
+
+    let result = User::from_redis_value(val);
+    assert_eq!(result, Ok(user()));
+}
+
+#[test]
+pub fn it_should_report_the_error_of_the_primary_serializer() {
+    let val = Value::BulkString("{\"id\":1}".as_bytes().into());
+    let result = User::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to User with serde_json: missing field `name` (line 1 column 8). (response was bulk-string('\"{\\\"id\\\":1}\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_serialize_with_the_primary_serializer() {
+    let bytes = user().to_redis_args();
+    assert_eq!(bytes[0], "{\"id\":1,\"name\":\"Ziggy\",\"addresses\":[{\"Street\":\"Downing\"},{\"Road\":\"Abbey\"}]}".as_bytes());
+}
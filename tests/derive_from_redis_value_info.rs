@@ -0,0 +1,148 @@
+use redis::{FromRedisValue, Value, VerbatimFormat};
+use redis_macros::FromRedisValue;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const INFO: &str = "# Server\r\nredis_version:7.2.4\r\nredis_mode:standalone\r\nuptime_in_seconds:42\r\n\r\n# Clients\r\nconnected_clients:2\r\n\r\n# Replication\r\nrole:master\r\n\r\n# Keyspace\r\ndb0:keys=3,expires=1,avg_ttl=0\r\n";
+
+const CLIENT_LIST: &str = "id=3 addr=127.0.0.1:52555 laddr=127.0.0.1:6379 fd=8 name= age=12 db=0\nid=4 addr=127.0.0.1:52556 laddr=127.0.0.1:6379 fd=9 name=worker age=3 db=1\n";
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Server {
+    redis_version: String,
+    uptime_in_seconds: u64,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Role {
+    Master,
+    Replica,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Keyspace {
+    keys: u64,
+    expires: u64,
+}
+
+#[derive(Debug, PartialEq, Deserialize, FromRedisValue)]
+#[redis_serializer(redis_macros::info)]
+struct Info {
+    server: Server,
+    connected_clients: u32,
+    role: Role,
+    db0: Option<Keyspace>,
+    db1: Option<Keyspace>,
+}
+
+#[derive(Debug, PartialEq, Deserialize, FromRedisValue)]
+#[redis_serializer(redis_macros::info)]
+struct Client {
+    id: u64,
+    addr: String,
+    name: Option<String>,
+    age: u64,
+}
+
+#[derive(Debug, PartialEq, Deserialize, FromRedisValue)]
+#[redis_serializer(redis_macros::info)]
+struct ClientList(Vec<Client>);
+
+#[derive(Debug, PartialEq, Deserialize, FromRedisValue)]
+#[redis_serializer(redis_macros::info)]
+struct Sections {
+    keyspace: HashMap<String, Keyspace>,
+}
+
+fn expected_info() -> Info {
+    Info {
+        server: Server {
+            redis_version: "7.2.4".to_string(),
+            uptime_in_seconds: 42,
+        },
+        connected_clients: 2,
+        role: Role::Master,
+        db0: Some(Keyspace {
+            keys: 3,
+            expires: 1,
+        }),
+        db1: None,
+    }
+}
+
+#[test]
+pub fn it_should_deserialize_info() {
+    let val = Value::BulkString(INFO.as_bytes().into());
+    assert_eq!(Info::from_redis_value(val), Ok(expected_info()));
+}
+
+#[test]
+pub fn it_should_deserialize_verbatim_strings() {
+    let val = Value::VerbatimString {
+        format: VerbatimFormat::Text,
+        text: INFO.to_string(),
+    };
+    assert_eq!(Info::from_redis_value(val), Ok(expected_info()));
+}
+
+#[test]
+pub fn it_should_deserialize_sections_as_maps() {
+    let val = Value::BulkString(INFO.as_bytes().into());
+    let result = Sections::from_redis_value(val).unwrap();
+    assert_eq!(
+        result.keyspace,
+        HashMap::from([(
+            "db0".to_string(),
+            Keyspace {
+                keys: 3,
+                expires: 1
+            }
+        )])
+    );
+}
+
+#[test]
+pub fn it_should_deserialize_client_info() {
+    let val = Value::BulkString(CLIENT_LIST.lines().next().unwrap().as_bytes().into());
+    let result = Client::from_redis_value(val);
+    assert_eq!(
+        result,
+        Ok(Client {
+            id: 3,
+            addr: "127.0.0.1:52555".to_string(),
+            name: None,
+            age: 12,
+        })
+    );
+}
+
+#[test]
+pub fn it_should_deserialize_client_list() {
+    let val = Value::BulkString(CLIENT_LIST.as_bytes().into());
+    let ClientList(clients) = ClientList::from_redis_value(val).unwrap();
+    assert_eq!(clients.len(), 2);
+    assert_eq!(clients[1].name.as_deref(), Some("worker"));
+    assert_eq!(clients[1].age, 3);
+}
+
+#[test]
+pub fn it_should_deserialize_into_maps() {
+    let result: HashMap<String, String> = redis_macros::info::from_str(INFO).unwrap();
+    assert_eq!(result["redis_mode"], "standalone");
+    assert_eq!(result["db0"], "keys=3,expires=1,avg_ttl=0");
+}
+
+#[test]
+pub fn it_should_report_invalid_values() {
+    let val = Value::BulkString("id=abc addr=x age=1".as_bytes().into());
+    let err = Client::from_redis_value(val).unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to Client with redis_macros::info: invalid value: string \"abc\", expected u64 (at `id`). (response was bulk-string('\"id=abc addr=x age=1\"'))");
+}
+
+#[test]
+pub fn it_should_report_invalid_lines() {
+    let val = Value::BulkString("id=3 addr\n".as_bytes().into());
+    let err = Client::from_redis_value(val).unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to Client with redis_macros::info: expected `key:value` or `key=value` pairs (line 1 column 6). (response was bulk-string('\"id=3 addr\\n\"'))");
+}
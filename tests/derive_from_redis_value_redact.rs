@@ -0,0 +1,180 @@
+use redis::{FromRedisValue, Value};
+use redis_macros::FromRedisValue;
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize, FromRedisValue)]
+struct User {
+    id: u32,
+    name: String,
+    #[redis_redact]
+    password: String,
+}
+
+#[derive(Debug, PartialEq, Deserialize, FromRedisValue)]
+#[serde(rename_all = "camelCase")]
+struct Token {
+    id: u32,
+    #[redis_redact]
+    access_token: String,
+    #[redis_redact]
+    #[serde(rename = "refresh")]
+    refresh_token: u64,
+}
+
+#[derive(Debug, PartialEq, Deserialize, FromRedisValue)]
+#[redis_errors(redact_payload)]
+struct Secret {
+    id: u32,
+    value: String,
+}
+
+#[derive(Debug, PartialEq, Deserialize, FromRedisValue)]
+#[redis_serializer(serde_yaml)]
+struct YamlUser {
+    id: u32,
+    #[redis_redact]
+    password: String,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Profile {
+    login: String,
+    password: String,
+}
+
+fn no_admin(account: &Account) -> Result<(), String> {
+    match account.profile.login.as_str() {
+        "admin" => Err(format!("{} uses the admin login", account.password)),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize, FromRedisValue)]
+#[redis_json(brackets = "strict")]
+#[redis_validate(no_admin)]
+struct Account {
+    id: u32,
+    profile: Profile,
+    #[redis_redact]
+    password: String,
+}
+
+#[test]
+pub fn it_should_redact_fields_from_the_response() {
+    let val = Value::BulkString(
+        "{\"id\":\"1\",\"name\":\"Ziggy\",\"password\":\"hunter2\"}"
+            .as_bytes()
+            .into(),
+    );
+    let result = User::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert!(!err.to_string().contains("hunter2"));
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to User with serde_json: invalid type: string \"1\", expected u32 (at `id`, line 1 column 9). (response was bulk-string('\"{\\\"id\\\":\\\"1\\\",\\\"name\\\":\\\"Ziggy\\\",\\\"password\\\":\\\"<redacted>\\\"}\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_redact_the_message_if_the_redacted_field_failed() {
+    let val = Value::BulkString(
+        "{\"id\":1,\"access_token\":\"hunter2\",\"refresh\":\"hunter3\"}"
+            .as_bytes()
+            .into(),
+    );
+    let result = Token::from_redis_value(val);
+    let err = result.unwrap_err().to_string();
+    assert!(!err.contains("hunter2"));
+    assert!(!err.contains("hunter3"));
+
+    let val = Value::BulkString(
+        "{\"id\":1,\"accessToken\":\"hunter2\",\"refresh\":\"hunter3\"}"
+            .as_bytes()
+            .into(),
+    );
+    let result = Token::from_redis_value(val);
+    let err = result.unwrap_err().to_string();
+    assert!(!err.contains("hunter2"));
+    assert!(!err.contains("hunter3"));
+    assert!(err.contains("<redacted> (at `refresh`, line 1 column 51)"));
+}
+
+#[test]
+pub fn it_should_redact_the_response_if_it_cannot_be_parsed() {
+    let val = Value::BulkString(
+        "{\"id\":1,\"name\":\"Ziggy\",\"password\":\"hunter2\""
+            .as_bytes()
+            .into(),
+    );
+    let result = User::from_redis_value(val);
+    let err = result.unwrap_err().to_string();
+    assert!(!err.contains("hunter2"));
+    assert!(err.ends_with("(response was <redacted>)"));
+
+    let val = Value::BulkString("id: one\npassword: hunter2\n".as_bytes().into());
+    let result = YamlUser::from_redis_value(val);
+    let err = result.unwrap_err().to_string();
+    assert!(!err.contains("hunter2"));
+    assert!(err.ends_with("(response was <redacted>)"));
+}
+
+#[test]
+pub fn it_should_redact_the_whole_payload() {
+    let val = Value::BulkString("{\"id\":1,\"value\":2}".as_bytes().into());
+    let result = Secret::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Incompatible type - Response type not deserializable to Secret with serde_json: <redacted> (at `value`, line 1 column 17). (response was <redacted>)"
+            .to_string()
+    );
+}
+
+#[test]
+pub fn it_should_not_redact_missing_responses() {
+    let result = Secret::from_redis_value(Value::Nil);
+    let err = result.unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Incompatible type - Response type not deserializable to Secret with serde_json: expected a bulk string. (response was nil)"
+            .to_string()
+    );
+}
+
+#[test]
+pub fn it_should_redact_nested_fields() {
+    let val = Value::BulkString(
+        "{\"id\":\"1\",\"profile\":{\"login\":\"ziggy\",\"password\":\"hunter2\"},\"password\":\"hunter3\"}"
+            .as_bytes()
+            .into(),
+    );
+    let err = Account::from_redis_value(val).unwrap_err().to_string();
+    assert!(!err.contains("hunter2"), "{err}");
+    assert!(!err.contains("hunter3"), "{err}");
+    assert!(
+        err.contains(
+            "\\\"profile\\\":{\\\"login\\\":\\\"ziggy\\\",\\\"password\\\":\\\"<redacted>\\\"}"
+        ),
+        "{err}"
+    );
+
+    let val = Value::BulkString(
+        "[{\"id\":1,\"profile\":{\"login\":\"ziggy\",\"password\":\"hunter2\"},\"password\":3}]"
+            .as_bytes()
+            .into(),
+    );
+    let err = Account::from_redis_value(val).unwrap_err().to_string();
+    assert!(err.contains("<redacted> (at `[0].password`"), "{err}");
+}
+
+#[test]
+pub fn it_should_redact_fields_from_validation_messages() {
+    let val = Value::BulkString(
+        "{\"id\":1,\"profile\":{\"login\":\"admin\",\"password\":\"hunter2\"},\"password\":\"hunter3\"}"
+            .as_bytes()
+            .into(),
+    );
+    let err = Account::from_redis_value(val).unwrap_err().to_string();
+    assert!(
+        err.contains("validation failed: <redacted> uses the admin login"),
+        "{err}"
+    );
+    assert!(!err.contains("hunter3"), "{err}");
+}
@@ -0,0 +1,104 @@
+use redis_macros::{JsonPath, RedisJsonPath, TypedJsonPath};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, RedisJsonPath)]
+enum Address {
+    Street(String),
+    Road(String),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, RedisJsonPath)]
+#[serde(rename_all = "camelCase")]
+struct Contact {
+    phone_number: String,
+    #[serde(rename = "e-mail")]
+    email: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Timestamp(u64);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, RedisJsonPath)]
+struct User {
+    id: u32,
+    #[serde(rename(serialize = "fullName", deserialize = "name"))]
+    name: String,
+    addresses: Vec<Address>,
+    contact: Contact,
+    #[serde(flatten)]
+    extra: Extra,
+    tags: HashMap<String, Vec<String>>,
+    #[redis_path(leaf)]
+    created: Timestamp,
+    #[serde(skip)]
+    #[allow(dead_code)]
+    cache: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, RedisJsonPath)]
+struct Extra {
+    score: f64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, RedisJsonPath)]
+struct Page<T> {
+    items: Vec<T>,
+    total: usize,
+}
+
+fn typed<P: TypedJsonPath>(path: P) -> String {
+    String::from_utf8(path.to_redis_args().remove(0)).unwrap()
+}
+
+#[test]
+pub fn it_should_generate_paths_from_the_fields() {
+    assert_eq!(User::path().as_str(), "$");
+    assert_eq!(User::path().id().as_str(), "$.id");
+    assert_eq!(User::path().addresses().as_str(), "$.addresses");
+    assert_eq!(User::path().addresses().index(1).as_str(), "$.addresses[1]");
+    assert_eq!(
+        User::path().tags().key("music").index(0).as_str(),
+        "$.tags.music[0]"
+    );
+    assert_eq!(User::path().created().as_str(), "$.created");
+}
+
+#[test]
+pub fn it_should_use_the_serialized_names() {
+    assert_eq!(User::path().name().as_str(), "$.fullName");
+    assert_eq!(
+        User::path().contact().phone_number().as_str(),
+        "$.contact.phoneNumber"
+    );
+    assert_eq!(
+        User::path().contact().email().as_str(),
+        "$.contact[\"e-mail\"]"
+    );
+    assert_eq!(User::path().extra().score().as_str(), "$.score");
+}
+
+#[test]
+pub fn it_should_know_the_type_of_the_target() {
+    let name: JsonPath<String> = User::path().name();
+    let address: JsonPath<Address> = User::path().addresses().index(0);
+    let email: JsonPath<String> = User::path().contact().email();
+    let created: JsonPath<Timestamp> = User::path().created();
+    assert_eq!(typed(name), "$.fullName");
+    assert_eq!(typed(&address), "$.addresses[0]");
+    assert_eq!(typed(email), "$.contact[\"e-mail\"]");
+    assert_eq!(typed(created), "$.created");
+    assert_eq!(typed(User::path().contact()), "$.contact");
+
+    let contact: JsonPath<Contact> = User::path().contact().into();
+    assert_eq!(contact.to_string(), "$.contact");
+}
+
+#[test]
+pub fn it_should_generate_paths_for_generic_structs() {
+    assert_eq!(
+        Page::<User>::path().items().index(2).contact().as_str(),
+        "$.items[2].contact"
+    );
+    assert_eq!(Page::<u32>::path().total().as_str(), "$.total");
+}
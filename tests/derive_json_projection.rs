@@ -0,0 +1,164 @@
+use redis::{FromRedisValue, ToRedisArgs, Value};
+use redis_macros::RedisJsonProjection;
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Address {
+    street: String,
+}
+
+#[derive(Debug, PartialEq, RedisJsonProjection)]
+struct UserSummary {
+    #[json_path("$.name")]
+    name: String,
+    #[json_path("$.addresses[0]")]
+    address: Address,
+}
+
+#[derive(Debug, PartialEq, RedisJsonProjection)]
+struct UserName {
+    #[json_path("$.name")]
+    name: String,
+}
+
+#[derive(Debug, PartialEq, RedisJsonProjection)]
+struct Credentials {
+    #[json_path("$.name")]
+    name: String,
+    #[json_path("$.password")]
+    #[redis_redact]
+    password: String,
+}
+
+#[derive(Debug, PartialEq, RedisJsonProjection)]
+#[serde_json_crate = "serde_json"]
+struct SerdeJsonName {
+    #[json_path("$.name")]
+    name: String,
+}
+
+#[derive(Debug, PartialEq, RedisJsonProjection)]
+struct Pair<T> {
+    #[json_path("$.first")]
+    first: T,
+    #[json_path("$.second")]
+    second: T,
+}
+
+#[test]
+pub fn it_should_generate_the_json_paths() {
+    assert_eq!(UserSummary::JSON_PATHS, &["$.name", "$.addresses[0]"]);
+    assert_eq!(
+        UserSummary::JSON_PATHS.to_redis_args(),
+        vec![b"$.name".to_vec(), b"$.addresses[0]".to_vec()]
+    );
+}
+
+#[test]
+pub fn it_should_deserialize_the_matches_of_each_path() {
+    let val = Value::BulkString(
+        "{\"$.name\":[\"Ziggy\"],\"$.addresses[0]\":[{\"street\":\"Downing\"}]}"
+            .as_bytes()
+            .into(),
+    );
+    let result = UserSummary::from_redis_value(val);
+    assert_eq!(
+        result,
+        Ok(UserSummary {
+            name: "Ziggy".to_string(),
+            address: Address {
+                street: "Downing".to_string()
+            },
+        })
+    );
+}
+
+#[test]
+pub fn it_should_deserialize_a_single_path() {
+    let val = Value::BulkString("[\"Ziggy\"]".as_bytes().into());
+    let result = UserName::from_redis_value(val);
+    assert_eq!(
+        result,
+        Ok(UserName {
+            name: "Ziggy".to_string()
+        })
+    );
+}
+
+#[test]
+pub fn it_should_deserialize_generic_projections() {
+    let val = Value::BulkString("{\"$.first\":[1],\"$.second\":[2]}".as_bytes().into());
+    let result = Pair::<u32>::from_redis_value(val);
+    assert_eq!(
+        result,
+        Ok(Pair {
+            first: 1,
+            second: 2
+        })
+    );
+}
+
+#[test]
+pub fn it_should_fail_with_the_path_of_the_failing_field() {
+    let val = Value::BulkString(
+        "{\"$.name\":[\"Ziggy\"],\"$.addresses[0]\":[{\"street\":1}]}"
+            .as_bytes()
+            .into(),
+    );
+    let result = UserSummary::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to UserSummary with serde_json: invalid type: integer `1`, expected a string (at `$.addresses[0].street`). (response was bulk-string('\"{\\\"$.name\\\":[\\\"Ziggy\\\"],\\\"$.addresses[0]\\\":[{\\\"street\\\":1}]}\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_fail_if_a_path_does_not_match_exactly_once() {
+    let val = Value::BulkString("{\"$.name\":[],\"$.addresses[0]\":[]}".as_bytes().into());
+    let result = UserSummary::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to UserSummary with serde_json: expected exactly one match, got 0 (at `$.name`). (response was bulk-string('\"{\\\"$.name\\\":[],\\\"$.addresses[0]\\\":[]}\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_fail_if_a_path_is_missing() {
+    let val = Value::BulkString("{\"$.name\":[\"Ziggy\"]}".as_bytes().into());
+    let result = UserSummary::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to UserSummary with serde_json: missing from the response (at `$.addresses[0]`). (response was bulk-string('\"{\\\"$.name\\\":[\\\"Ziggy\\\"]}\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_fail_if_input_is_missing() {
+    let result = UserSummary::from_redis_value(Value::Nil);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to UserSummary with serde_json: expected a bulk string. (response was nil)".to_string());
+}
+
+#[test]
+pub fn it_should_hide_the_redacted_paths() {
+    let val = Value::BulkString(
+        "{\"$.name\":[\"Ziggy\"],\"$.password\":[4242]}"
+            .as_bytes()
+            .into(),
+    );
+    let err = Credentials::from_redis_value(val).unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to Credentials with serde_json: <redacted> (at `$.password`). (response was bulk-string('\"{\\\"$.name\\\":[\\\"Ziggy\\\"],\\\"$.password\\\":\\\"<redacted>\\\"}\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_use_the_serde_json_crate() {
+    let val = Value::BulkString("[\"Ziggy\"]".as_bytes().into());
+    let result = SerdeJsonName::from_redis_value(val);
+    assert_eq!(
+        result,
+        Ok(SerdeJsonName {
+            name: "Ziggy".to_string()
+        })
+    );
+}
+
+#[test]
+pub fn it_should_fail_if_the_paths_are_not_an_object() {
+    let val = Value::BulkString("[\"Ziggy\"]".as_bytes().into());
+    let err = UserSummary::from_redis_value(val).unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to UserSummary with serde_json: invalid type: sequence, expected a map. (response was bulk-string('\"[\\\"Ziggy\\\"]\"'))".to_string());
+}
@@ -0,0 +1,109 @@
+use redis::{FromRedisValue, ToRedisArgs, Value, VerbatimFormat};
+use redis_macros::{DeserializeErrorKind, FromRedisValue, ToRedisArgs};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, FromRedisValue, ToRedisArgs)]
+#[redis_enum(as_str)]
+enum Status {
+    Active,
+    Inactive,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+#[redis_enum(as_str)]
+#[serde(rename_all = "snake_case")]
+enum Plan {
+    FreeTier,
+    #[serde(rename = "pro")]
+    Professional,
+}
+
+#[derive(Debug, PartialEq, FromRedisValue, ToRedisArgs)]
+#[redis_enum(repr = u8)]
+enum Priority {
+    Low = 1,
+    Medium,
+    High = 10,
+}
+
+#[test]
+pub fn it_should_write_the_variant_name_without_quotes() {
+    assert_eq!(Status::Active.to_redis_args(), vec![b"Active".to_vec()]);
+    assert_eq!(Plan::FreeTier.to_redis_args(), vec![b"free_tier".to_vec()]);
+    assert_eq!(Plan::Professional.to_redis_args(), vec![b"pro".to_vec()]);
+}
+
+#[test]
+pub fn it_should_read_the_variant_name() {
+    let val = Value::BulkString(b"Inactive".to_vec());
+    assert_eq!(Status::from_redis_value(val), Ok(Status::Inactive));
+
+    let val = Value::SimpleString("free_tier".to_string());
+    assert_eq!(Plan::from_redis_value(val), Ok(Plan::FreeTier));
+
+    let val = Value::BulkString(b"pro".to_vec());
+    assert_eq!(Plan::from_redis_value(val), Ok(Plan::Professional));
+
+    let val = Value::VerbatimString {
+        format: VerbatimFormat::Text,
+        text: "Active".to_string(),
+    };
+    assert_eq!(Status::from_redis_value(val), Ok(Status::Active));
+}
+
+#[test]
+pub fn it_should_match_the_serde_names() {
+    let json = serde_json::to_string(&Plan::FreeTier).unwrap();
+    assert_eq!(json, "\"free_tier\"");
+}
+
+#[test]
+pub fn it_should_fail_on_unknown_variant_names() {
+    let val = Value::BulkString(b"Deleted".to_vec());
+    let err = Status::from_redis_value(val).unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to Status with redis_enum(as_str): unknown variant `Deleted`, expected one of `Active`, `Inactive`. (response was bulk-string('\"Deleted\"'))");
+
+    let err = Status::from_redis_value(Value::Nil).unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to Status with redis_enum(as_str): expected a bulk string. (response was nil)");
+}
+
+#[test]
+pub fn it_should_write_the_discriminant() {
+    assert_eq!(Priority::Low.to_redis_args(), vec![b"1".to_vec()]);
+    assert_eq!(Priority::Medium.to_redis_args(), vec![b"2".to_vec()]);
+    assert_eq!(Priority::High.to_redis_args(), vec![b"10".to_vec()]);
+    assert_eq!(
+        Priority::High.describe_numeric_behavior(),
+        redis::NumericBehavior::NumberIsInteger
+    );
+}
+
+#[test]
+pub fn it_should_read_the_discriminant() {
+    assert_eq!(
+        Priority::from_redis_value(Value::Int(2)),
+        Ok(Priority::Medium)
+    );
+    let val = Value::BulkString(b"10".to_vec());
+    assert_eq!(Priority::from_redis_value(val), Ok(Priority::High));
+}
+
+#[test]
+pub fn it_should_fail_on_unknown_discriminants() {
+    let err = Priority::from_redis_value(Value::Int(3)).unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to Priority with redis_enum(repr = u8): unknown variant `3`, expected one of `1`, `2`, `10`. (response was int(3))");
+
+    assert!(Priority::from_redis_value(Value::BulkString(b"high".to_vec())).is_err());
+}
+
+#[test]
+pub fn it_should_expose_the_unknown_variant_kind() {
+    let kind = DeserializeErrorKind::unknown_variant("Deleted", ["Active", "Inactive"]);
+    assert_eq!(
+        kind,
+        DeserializeErrorKind::UnknownVariant {
+            value: "Deleted".to_string(),
+            expected: vec!["Active".to_string(), "Inactive".to_string()],
+        }
+    );
+}
@@ -0,0 +1,144 @@
+use redis::{FromRedisValue, ToRedisArgs, Value};
+use redis_macros::{Envelope, RedisValue, ValidateArgs, WithFormat};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, RedisValue)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, RedisValue)]
+#[redis(format = serde_yaml, fallback(serde_json))]
+#[redis(envelope(schema = 2))]
+struct YamlUser {
+    id: u32,
+    name: String,
+}
+
+fn non_negative(account: &Account) -> Result<(), &'static str> {
+    if account.balance < 0 {
+        return Err("balance is negative");
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, RedisValue)]
+#[redis(validate(non_negative), json(brackets = "off"))]
+struct Account {
+    balance: i64,
+    #[redis(redact)]
+    secret: String,
+}
+
+#[derive(Debug, PartialEq, RedisValue)]
+#[redis(transparent)]
+struct UserId(u64);
+
+#[derive(Debug, PartialEq, RedisValue)]
+#[redis(enum(as_str))]
+enum Status {
+    Active,
+    Inactive,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, RedisValue)]
+#[redis(key = "session:{user_id}:{id:04}:{{active}}")]
+struct Session {
+    id: u32,
+    user_id: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, RedisValue)]
+#[redis(
+    crate = "redis_macros::__private::redis",
+    serde_json_crate = "serde_json"
+)]
+struct Configured {
+    id: u32,
+}
+
+#[test]
+pub fn it_should_implement_both_traits() {
+    let user = User {
+        id: 1,
+        name: "Ziggy".to_string(),
+    };
+    let args = user.to_redis_args();
+    assert_eq!(args, vec![b"{\"id\":1,\"name\":\"Ziggy\"}".to_vec()]);
+    assert_eq!(
+        User::from_redis_value(Value::BulkString(args[0].clone())),
+        Ok(user)
+    );
+
+    let configured = Configured { id: 2 };
+    let args = configured.to_redis_args();
+    assert_eq!(
+        Configured::from_redis_value(Value::BulkString(args[0].clone())),
+        Ok(configured)
+    );
+}
+
+#[test]
+pub fn it_should_use_the_format_and_envelope() {
+    let user = YamlUser {
+        id: 1,
+        name: "Ziggy".to_string(),
+    };
+    let args = user.to_redis_args();
+    assert_eq!(
+        args[0],
+        [
+            &[0xFE, Envelope::FORMAT_YAML, 0, 0, 0, 0, 2],
+            "id: 1\nname: Ziggy\n".as_bytes()
+        ]
+        .concat()
+    );
+    assert_eq!(
+        YamlUser::from_redis_value(Value::BulkString(args[0].clone())),
+        Ok(YamlUser {
+            id: 1,
+            name: "Ziggy".to_string(),
+        })
+    );
+
+    let WithFormat(_, format) = WithFormat::<YamlUser>::from_redis_value(Value::BulkString(
+        b"{\"id\":1,\"name\":\"Ziggy\"}".to_vec(),
+    ))
+    .unwrap();
+    assert_eq!(format, "serde_yaml");
+}
+
+#[test]
+pub fn it_should_use_the_validation_and_redaction() {
+    let account = Account {
+        balance: -1,
+        secret: "hunter2".to_string(),
+    };
+    assert!(account.validated().is_err());
+
+    let err = Account::from_redis_value(Value::BulkString(
+        b"{\"balance\":-1,\"secret\":\"hunter2\"}".to_vec(),
+    ))
+    .unwrap_err()
+    .to_string();
+    assert!(err.contains("balance is negative"), "{err}");
+    assert!(!err.contains("hunter2"), "{err}");
+}
+
+#[test]
+pub fn it_should_write_plain_values() {
+    assert_eq!(UserId(3).to_redis_args(), vec![b"3".to_vec()]);
+    assert_eq!(UserId::from_redis_value(Value::Int(3)), Ok(UserId(3)));
+    assert_eq!(Status::Inactive.to_redis_args(), vec![b"Inactive".to_vec()]);
+    assert_eq!(
+        Status::from_redis_value(Value::SimpleString("Active".to_string())),
+        Ok(Status::Active)
+    );
+}
+
+#[test]
+pub fn it_should_format_the_key_template() {
+    let session = Session { id: 7, user_id: 3 };
+    assert_eq!(session.redis_key(), "session:3:0007:{active}");
+}
@@ -0,0 +1,97 @@
+use redis::{FromRedisValue, Value};
+use redis_macros::{DeserializeErrorKind, FromRedisValue};
+
+#[derive(Debug, PartialEq, FromRedisValue)]
+#[redis_reply(tuple)]
+struct Scored {
+    member: String,
+    score: f64,
+}
+
+#[derive(Debug, PartialEq, FromRedisValue)]
+#[redis_reply(tuple)]
+struct PendingSummary(
+    u64,
+    Option<String>,
+    Option<String>,
+    Option<Vec<(String, u64)>>,
+);
+
+#[test]
+pub fn it_should_map_the_elements_to_the_fields() {
+    let val = Value::Array(vec![
+        Value::BulkString(b"Ziggy".to_vec()),
+        Value::BulkString(b"1.5".to_vec()),
+    ]);
+    let result = Scored::from_redis_value(val);
+    assert_eq!(
+        result,
+        Ok(Scored {
+            member: "Ziggy".to_string(),
+            score: 1.5
+        })
+    );
+}
+
+#[test]
+pub fn it_should_map_the_elements_to_tuple_struct_fields() {
+    let val = Value::Array(vec![
+        Value::Int(2),
+        Value::BulkString(b"1-0".to_vec()),
+        Value::BulkString(b"2-0".to_vec()),
+        Value::Array(vec![Value::Array(vec![
+            Value::BulkString(b"consumer".to_vec()),
+            Value::BulkString(b"2".to_vec()),
+        ])]),
+    ]);
+    let result = PendingSummary::from_redis_value(val);
+    assert_eq!(
+        result,
+        Ok(PendingSummary(
+            2,
+            Some("1-0".to_string()),
+            Some("2-0".to_string()),
+            Some(vec![("consumer".to_string(), 2)])
+        ))
+    );
+
+    let val = Value::Array(vec![Value::Int(0), Value::Nil, Value::Nil, Value::Nil]);
+    let result = PendingSummary::from_redis_value(val);
+    assert_eq!(result, Ok(PendingSummary(0, None, None, None)));
+}
+
+#[test]
+pub fn it_should_check_the_number_of_elements() {
+    let val = Value::Array(vec![Value::BulkString(b"Ziggy".to_vec())]);
+    let err = Scored::from_redis_value(val).unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to Scored with redis_reply(tuple): expected an array of 2 elements, got 1. (response was array([bulk-string('\"Ziggy\"')]))");
+
+    let err = Scored::from_redis_value(Value::Nil).unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to Scored with redis_reply(tuple): expected an array. (response was nil)");
+}
+
+#[test]
+pub fn it_should_name_the_failing_field() {
+    let val = Value::Array(vec![
+        Value::BulkString(b"Ziggy".to_vec()),
+        Value::BulkString(b"high".to_vec()),
+    ]);
+    let err = Scored::from_redis_value(val).unwrap_err().to_string();
+    assert!(
+        err.starts_with("Incompatible type - Response type not deserializable to Scored with redis_reply(tuple): at field `score`: "),
+        "{err}"
+    );
+}
+
+#[test]
+pub fn it_should_expose_the_field_kind() {
+    let error = redis::ParsingError::from("expected a number");
+    let kind = DeserializeErrorKind::field("score", &error);
+    assert_eq!(
+        kind,
+        DeserializeErrorKind::Field {
+            name: "score",
+            message: "expected a number".to_string()
+        }
+    );
+}
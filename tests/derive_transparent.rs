@@ -0,0 +1,71 @@
+use redis::{FromRedisValue, ToRedisArgs, Value};
+use redis_macros::{FromRedisValue, ToRedisArgs};
+
+#[derive(Debug, PartialEq, FromRedisValue, ToRedisArgs)]
+#[redis_transparent]
+struct UserId(u64);
+
+#[derive(Debug, PartialEq, FromRedisValue, ToRedisArgs)]
+#[redis_transparent]
+struct Name {
+    value: String,
+}
+
+#[derive(Debug, PartialEq, FromRedisValue, ToRedisArgs)]
+#[redis_transparent]
+struct Wrapper<T>(T);
+
+#[test]
+pub fn it_should_write_the_inner_value() {
+    assert_eq!(UserId(123).to_redis_args(), vec![b"123".to_vec()]);
+    assert_eq!(
+        Name {
+            value: "Ziggy".to_string()
+        }
+        .to_redis_args(),
+        vec![b"Ziggy".to_vec()]
+    );
+    assert_eq!(Wrapper(1.5f64).to_redis_args(), vec![b"1.5".to_vec()]);
+}
+
+#[test]
+pub fn it_should_describe_the_numeric_behavior_of_the_inner_value() {
+    assert_eq!(
+        UserId(123).describe_numeric_behavior(),
+        redis::NumericBehavior::NumberIsInteger
+    );
+}
+
+#[test]
+pub fn it_should_read_the_replies_of_the_inner_type() {
+    assert_eq!(UserId::from_redis_value(Value::Int(123)), Ok(UserId(123)));
+    assert_eq!(
+        UserId::from_redis_value(Value::BulkString(b"123".to_vec())),
+        Ok(UserId(123))
+    );
+    assert_eq!(
+        Wrapper::<f64>::from_redis_value(Value::Double(1.5)),
+        Ok(Wrapper(1.5))
+    );
+    assert_eq!(
+        Name::from_redis_value(Value::SimpleString("Ziggy".to_string())),
+        Ok(Name {
+            value: "Ziggy".to_string()
+        })
+    );
+}
+
+#[test]
+pub fn it_should_fail_like_the_inner_type() {
+    let val = Value::BulkString(b"abc".to_vec());
+    let expected = u64::from_redis_value(val.clone()).unwrap_err().to_string();
+    let result = UserId::from_redis_value(val).unwrap_err().to_string();
+    assert_eq!(result, expected);
+}
+
+#[test]
+pub fn it_should_read_collections_of_newtypes() {
+    let val = Value::Array(vec![Value::Int(1), Value::Int(2)]);
+    let result = Vec::<UserId>::from_redis_value(val);
+    assert_eq!(result, Ok(vec![UserId(1), UserId(2)]));
+}
@@ -0,0 +1,78 @@
+use redis::{FromRedisValue, ToRedisArgs, Value};
+use redis_macros::{FromRedisValue, ToRedisArgs, ValidateArgs};
+use serde::{Deserialize, Serialize};
+
+mod validators {
+    use super::Account;
+
+    pub fn non_negative_balance(account: &Account) -> Result<(), String> {
+        if account.balance < 0 {
+            return Err(format!("balance is negative: {}", account.balance));
+        }
+        Ok(())
+    }
+}
+
+fn non_empty_name(account: &Account) -> Result<(), &'static str> {
+    if account.name.is_empty() {
+        return Err("name is empty");
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+#[redis_validate(validators::non_negative_balance)]
+#[redis_validate(non_empty_name)]
+struct Account {
+    name: String,
+    balance: i64,
+}
+
+#[test]
+pub fn it_should_accept_valid_values() {
+    let val = Value::BulkString("{\"name\":\"Ziggy\",\"balance\":10}".as_bytes().into());
+    let result = Account::from_redis_value(val);
+    assert_eq!(
+        result,
+        Ok(Account {
+            name: "Ziggy".to_string(),
+            balance: 10
+        })
+    );
+}
+
+#[test]
+pub fn it_should_fail_if_the_validator_fails() {
+    let val = Value::BulkString("{\"name\":\"Ziggy\",\"balance\":-10}".as_bytes().into());
+    let result = Account::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to Account with serde_json: validation failed: balance is negative: -10. (response was bulk-string('\"{\\\"name\\\":\\\"Ziggy\\\",\\\"balance\\\":-10}\"'))".to_string());
+
+    let val = Value::BulkString("[{\"name\":\"\",\"balance\":10}]".as_bytes().into());
+    let result = Account::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to Account with serde_json: validation failed: name is empty. (response was bulk-string('\"[{\\\"name\\\":\\\"\\\",\\\"balance\\\":10}]\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_validate_before_writing() {
+    let account = Account {
+        name: "Ziggy".to_string(),
+        balance: 10,
+    };
+    let bytes = account.validated().unwrap().to_redis_args();
+    assert_eq!(bytes[0], "{\"name\":\"Ziggy\",\"balance\":10}".as_bytes());
+
+    let account = Account {
+        name: "Ziggy".to_string(),
+        balance: -10,
+    };
+    let err = account.validated().unwrap_err();
+    assert_eq!(err.target(), "Account");
+    assert_eq!(
+        err.to_string(),
+        "Validation of Account failed: balance is negative: -10"
+    );
+    let err = redis::RedisError::from(err);
+    assert_eq!(err.kind(), redis::ErrorKind::Client);
+}
@@ -0,0 +1,118 @@
+use redis::{FromRedisValue, ToRedisArgs, Value};
+use redis_macros::{FromRedisValue, RedisJsonPath, RedisJsonProjection, ToRedisArgs};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{borrow::Cow, marker::PhantomData};
+
+/// A type that is not serializable, only used as a marker
+#[derive(Debug, PartialEq)]
+struct Admin;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+#[redis_bound(deserialize = "", serialize = "")]
+struct Id<T> {
+    id: u32,
+    #[serde(skip)]
+    marker: PhantomData<T>,
+}
+
+trait Schema {
+    type Payload;
+}
+
+struct V1;
+
+impl Schema for V1 {
+    type Payload = String;
+}
+
+#[derive(Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+#[redis_bound(
+    deserialize = "S::Payload: DeserializeOwned",
+    serialize = "S::Payload: Serialize"
+)]
+struct Message<S: Schema> {
+    payload: S::Payload,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs, RedisJsonPath)]
+struct Named<'a, T, const N: usize> {
+    name: Cow<'a, str>,
+    values: Vec<T>,
+}
+
+#[derive(Serialize, RedisJsonPath)]
+#[redis_bound(serialize = "")]
+struct Tagged<T> {
+    id: u32,
+    #[serde(skip)]
+    marker: PhantomData<T>,
+}
+
+#[derive(Debug, PartialEq, RedisJsonProjection)]
+struct NameOnly<'a, const N: usize> {
+    #[json_path("$.name")]
+    name: Cow<'a, str>,
+}
+
+#[test]
+pub fn it_should_use_custom_bounds_for_phantom_markers() {
+    let id = Id::<Admin> {
+        id: 1,
+        marker: PhantomData,
+    };
+    assert_eq!(id.to_redis_args(), vec![b"{\"id\":1}".to_vec()]);
+
+    let val = Value::BulkString(b"{\"id\":1}".to_vec());
+    assert_eq!(Id::<Admin>::from_redis_value(val), Ok(id));
+}
+
+#[test]
+pub fn it_should_use_custom_bounds_for_associated_types() {
+    let message = Message::<V1> {
+        payload: "hello".to_string(),
+    };
+    let args = message.to_redis_args();
+    assert_eq!(args, vec![b"{\"payload\":\"hello\"}".to_vec()]);
+
+    let result = Message::<V1>::from_redis_value(Value::BulkString(args[0].clone())).unwrap();
+    assert_eq!(result.payload, "hello");
+}
+
+#[test]
+pub fn it_should_derive_with_lifetimes_and_const_generics() {
+    let named = Named::<u32, 3> {
+        name: Cow::Borrowed("Ziggy"),
+        values: vec![1, 2, 3],
+    };
+    let args = named.to_redis_args();
+    assert_eq!(
+        args,
+        vec![b"{\"name\":\"Ziggy\",\"values\":[1,2,3]}".to_vec()]
+    );
+
+    let result = Named::<u32, 3>::from_redis_value(Value::BulkString(args[0].clone()));
+    assert_eq!(result, Ok(named));
+
+    let path = Named::<u32, 3>::path().name();
+    assert_eq!(path.as_str(), "$.name");
+}
+
+#[test]
+pub fn it_should_derive_projections_with_lifetimes_and_const_generics() {
+    assert_eq!(NameOnly::<1>::JSON_PATHS, &["$.name"]);
+
+    let val = Value::BulkString(b"[\"Ziggy\"]".to_vec());
+    let result = NameOnly::<1>::from_redis_value(val);
+    assert_eq!(
+        result,
+        Ok(NameOnly {
+            name: Cow::Borrowed("Ziggy")
+        })
+    );
+}
+
+#[test]
+pub fn it_should_use_the_custom_bounds_for_paths() {
+    // Admin doesn't implement HasJsonPath
+    assert_eq!(Tagged::<Admin>::path().id().as_str(), "$.id");
+}
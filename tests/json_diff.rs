@@ -0,0 +1,208 @@
+use redis_macros::{JsonDiff, JsonOp};
+use serde::Serialize;
+use serde_json::json;
+
+#[derive(Clone, Serialize)]
+struct Contact {
+    email: String,
+    phone: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct User {
+    id: u32,
+    name: String,
+    tags: Vec<String>,
+    contact: Contact,
+}
+
+fn user() -> User {
+    User {
+        id: 1,
+        name: "Ziggy".to_string(),
+        tags: vec!["glam".to_string(), "rock".to_string()],
+        contact: Contact {
+            email: "ziggy@mars.com".to_string(),
+            phone: Some("555".to_string()),
+        },
+    }
+}
+
+#[test]
+pub fn it_should_not_update_equal_values() {
+    let diff = JsonDiff::new(&user(), &user()).unwrap();
+    assert!(diff.is_empty());
+    assert_eq!(diff.pipeline("user").cmd_iter().count(), 0);
+}
+
+#[test]
+pub fn it_should_set_a_single_changed_field() {
+    let mut modified = user();
+    modified.name = "Bowie".to_string();
+    modified.contact.email = "bowie@mars.com".to_string();
+    let diff = JsonDiff::new(&user(), &modified).unwrap();
+    assert_eq!(
+        diff.ops(),
+        &[
+            JsonOp::Set("$.name".to_string(), json!("Bowie")),
+            JsonOp::Set("$.contact.email".to_string(), json!("bowie@mars.com")),
+        ]
+    );
+}
+
+#[test]
+pub fn it_should_merge_multiple_changed_fields() {
+    let mut modified = user();
+    modified.id = 2;
+    modified.name = "Bowie".to_string();
+    let diff = JsonDiff::new(&user(), &modified).unwrap();
+    assert_eq!(
+        diff.ops(),
+        &[JsonOp::Merge(
+            "$".to_string(),
+            json!({ "id": 2, "name": "Bowie" })
+        )]
+    );
+}
+
+#[test]
+pub fn it_should_compare_arrays_by_element_if_the_length_is_the_same() {
+    let mut modified = user();
+    modified.tags[1] = "pop".to_string();
+    let diff = JsonDiff::new(&user(), &modified).unwrap();
+    assert_eq!(
+        diff.ops(),
+        &[JsonOp::Set("$.tags[1]".to_string(), json!("pop"))]
+    );
+
+    modified.tags.push("soul".to_string());
+    let diff = JsonDiff::new(&user(), &modified).unwrap();
+    assert_eq!(
+        diff.ops(),
+        &[JsonOp::Set(
+            "$.tags".to_string(),
+            json!(["glam", "pop", "soul"])
+        )]
+    );
+}
+
+#[test]
+pub fn it_should_delete_removed_fields_and_set_nulls() {
+    let old = json!({ "a": 1, "b": { "c": 2 }, "d": 3 });
+    let new = json!({ "b": { "c": null }, "d": null });
+    let diff = JsonDiff::from_values(&old, &new);
+    assert_eq!(
+        diff.ops(),
+        &[
+            JsonOp::Del("$.a".to_string()),
+            JsonOp::Set("$.d".to_string(), json!(null)),
+            JsonOp::Set("$.b.c".to_string(), json!(null)),
+        ]
+    );
+
+    let new = json!({ "b": { "c": 2 }, "e f": 4 });
+    let diff = JsonDiff::from_values(&old, &new);
+    assert_eq!(
+        diff.ops(),
+        &[JsonOp::Merge(
+            "$".to_string(),
+            json!({ "a": null, "d": null, "e f": 4 })
+        )]
+    );
+
+    let mut modified = user();
+    modified.contact.phone = None;
+    let diff = JsonDiff::new(&user(), &modified).unwrap();
+    assert_eq!(
+        diff.ops(),
+        &[JsonOp::Set("$.contact.phone".to_string(), json!(null))]
+    );
+}
+
+#[test]
+pub fn it_should_set_added_objects_with_nested_nulls() {
+    #[derive(Serialize)]
+    struct Account {
+        id: u32,
+        contact: Option<Contact>,
+        name: Option<String>,
+    }
+
+    let old = Account {
+        id: 1,
+        contact: None,
+        name: None,
+    };
+    let new = Account {
+        id: 1,
+        contact: Some(Contact {
+            email: "ziggy@mars.com".to_string(),
+            phone: None,
+        }),
+        name: Some("Ziggy".to_string()),
+    };
+    let diff = JsonDiff::new(&old, &new).unwrap();
+    assert_eq!(
+        diff.ops(),
+        &[
+            JsonOp::Set("$.name".to_string(), json!("Ziggy")),
+            JsonOp::Set(
+                "$.contact".to_string(),
+                json!({ "email": "ziggy@mars.com", "phone": null })
+            ),
+        ]
+    );
+
+    let old = json!({ "a": 1 });
+    let new = json!({ "a": 1, "b": { "c": { "d": null } }, "e": 2, "f": 3 });
+    let diff = JsonDiff::from_values(&old, &new);
+    assert_eq!(
+        diff.ops(),
+        &[
+            JsonOp::Merge("$".to_string(), json!({ "e": 2, "f": 3 })),
+            JsonOp::Set("$.b".to_string(), json!({ "c": { "d": null } })),
+        ]
+    );
+}
+
+#[test]
+pub fn it_should_replace_values_with_different_types() {
+    let diff = JsonDiff::from_values(&json!({ "a": [1] }), &json!(1));
+    assert_eq!(diff.ops(), &[JsonOp::Set("$".to_string(), json!(1))]);
+
+    let diff = JsonDiff::from_values(&json!({ "a b": { "c": 1 } }), &json!({ "a b": [1] }));
+    assert_eq!(
+        diff.ops(),
+        &[JsonOp::Set("$[\"a b\"]".to_string(), json!([1]))]
+    );
+}
+
+#[test]
+pub fn it_should_create_a_pipeline() {
+    let mut modified = user();
+    modified.id = 2;
+    modified.name = "Bowie".to_string();
+    modified.tags.clear();
+    modified.contact.phone = None;
+    let diff = JsonDiff::new(&user(), &modified).unwrap();
+    let commands = diff
+        .pipeline("user")
+        .cmd_iter()
+        .map(|cmd| {
+            cmd.args_iter()
+                .map(|arg| match arg {
+                    redis::Arg::Simple(arg) => String::from_utf8(arg.to_vec()).unwrap(),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        commands,
+        vec![
+            "JSON.MERGE user $ {\"id\":2,\"name\":\"Bowie\",\"tags\":[]}",
+            "JSON.SET user $.contact.phone null",
+        ]
+    );
+}
@@ -0,0 +1,87 @@
+use redis::{FromRedisValue, ToRedisArgs, Value};
+use redis_macros::{
+    DefaultJsonEngine, FromRedisValue, Json, JsonEngine, JsonWith, SerdeJson, ToRedisArgs,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Address {
+    Street(String),
+    Road(String),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+struct User {
+    id: u32,
+    name: String,
+    addresses: Vec<Address>,
+}
+
+fn user() -> User {
+    User {
+        id: 1,
+        name: "Ziggy".to_string(),
+        addresses: vec![
+            Address::Street("Downing".to_string()),
+            Address::Road("Abbey".to_string()),
+        ],
+    }
+}
+
+const USER: &str =
+    "{\"id\":1,\"name\":\"Ziggy\",\"addresses\":[{\"Street\":\"Downing\"},{\"Road\":\"Abbey\"}]}";
+
+#[test]
+pub fn it_should_select_the_engine_from_the_features() {
+    let expected = if cfg!(feature = "sonic-rs") {
+        "sonic_rs"
+    } else if cfg!(feature = "simd-json") {
+        "simd_json"
+    } else {
+        "serde_json"
+    };
+    assert_eq!(DefaultJsonEngine::NAME, expected);
+}
+
+#[test]
+pub fn it_should_roundtrip_derived_types_with_the_engine() {
+    let args = user().to_redis_args();
+    assert_eq!(args, vec![USER.as_bytes().to_vec()]);
+
+    let result = User::from_redis_value(Value::BulkString(args[0].clone()));
+    assert_eq!(result, Ok(user()));
+}
+
+#[test]
+pub fn it_should_roundtrip_json_with_wrapper_with_the_engine() {
+    let args = JsonWith::<User, DefaultJsonEngine>::new(user()).to_redis_args();
+    assert_eq!(args, vec![USER.as_bytes().to_vec()]);
+
+    let val = Value::BulkString(format!("[{USER}]").into_bytes());
+    let result = JsonWith::<User, DefaultJsonEngine>::from_redis_value(val);
+    assert_eq!(result, Ok(JsonWith::new(user())));
+}
+
+#[test]
+pub fn it_should_report_the_same_errors_with_every_engine() {
+    let val = Value::BulkString(
+        "[{\"id\":1,\"name\":\"Ziggy\",\"addresses\":[{\"Street\":\"Downing\"},{\"Road\":2}]}]"
+            .as_bytes()
+            .into(),
+    );
+    let expected = "Incompatible type - Response type not deserializable to json_engine::User with serde_json: invalid type: integer `2`, expected a string (at `[0].addresses[1].Road`, line 1 column 67). (response was bulk-string('\"[{\\\"id\\\":1,\\\"name\\\":\\\"Ziggy\\\",\\\"addresses\\\":[{\\\"Street\\\":\\\"Downing\\\"},{\\\"Road\\\":2}]}]\"'))";
+    let err = JsonWith::<User, DefaultJsonEngine>::from_redis_value(val.clone()).unwrap_err();
+    assert_eq!(err.to_string(), expected);
+    let err = Json::<User>::from_redis_value(val).unwrap_err();
+    assert_eq!(err.to_string(), expected);
+}
+
+#[test]
+pub fn it_should_use_serde_json_by_default_for_the_wrappers() {
+    let args = Json(user()).to_redis_args();
+    assert_eq!(args, vec![USER.as_bytes().to_vec()]);
+
+    let val = Value::BulkString(format!("[{USER}]").into_bytes());
+    let parsed: JsonWith<User> = JsonWith::<User, SerdeJson>::from_redis_value(val).unwrap();
+    assert_eq!(parsed.into_inner(), user());
+}
@@ -0,0 +1,108 @@
+use redis::{FromRedisValue, Value};
+use redis_macros::{JsonAll, JsonPopped, JsonType, PerMatch};
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Address {
+    street: String,
+}
+
+#[test]
+pub fn it_should_deserialize_lengths_per_match() {
+    let val = Value::Array(vec![Value::Int(5), Value::Nil]);
+    let result = PerMatch::<Option<usize>>::from_redis_value(val);
+    assert_eq!(result, Ok(PerMatch(vec![Some(5), None])));
+
+    let result = PerMatch::<Option<usize>>::from_redis_value(Value::Int(3));
+    assert_eq!(result, Ok(PerMatch(vec![Some(3)])));
+}
+
+#[test]
+pub fn it_should_deserialize_object_keys_per_match() {
+    let val = Value::Array(vec![
+        Value::Array(vec![
+            Value::BulkString("name".as_bytes().into()),
+            Value::BulkString("id".as_bytes().into()),
+        ]),
+        Value::Nil,
+    ]);
+    let result = PerMatch::<Option<Vec<String>>>::from_redis_value(val);
+    assert_eq!(
+        result,
+        Ok(PerMatch(vec![
+            Some(vec!["name".to_string(), "id".to_string()]),
+            None
+        ]))
+    );
+}
+
+#[test]
+pub fn it_should_deserialize_json_types() {
+    let val = Value::Array(vec![
+        Value::BulkString("integer".as_bytes().into()),
+        Value::BulkString("object".as_bytes().into()),
+        Value::SimpleString("array".to_string()),
+    ]);
+    let result = PerMatch::<JsonType>::from_redis_value(val);
+    assert_eq!(
+        result,
+        Ok(PerMatch(vec![
+            JsonType::Integer,
+            JsonType::Object,
+            JsonType::Array
+        ]))
+    );
+
+    let val = Value::BulkString("boolean".as_bytes().into());
+    let result = JsonType::from_redis_value(val);
+    assert_eq!(result, Ok(JsonType::Boolean));
+    assert_eq!(JsonType::Boolean.to_string(), "boolean");
+}
+
+#[test]
+pub fn it_should_fail_if_the_json_type_is_unknown() {
+    let val = Value::BulkString("date".as_bytes().into());
+    let result = JsonType::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to JsonType with JSON.TYPE: unknown variant `date`, expected one of `null`, `boolean`, `integer`, `number`, `string`, `object`, `array`. (response was bulk-string('\"date\"'))".to_string());
+
+    let err = JsonType::from_redis_value(Value::Int(1)).unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to JsonType with JSON.TYPE: expected a bulk string. (response was int(1))".to_string());
+}
+
+#[test]
+pub fn it_should_deserialize_popped_elements() {
+    let val = Value::Array(vec![
+        Value::BulkString("{\"street\":\"Downing\"}".as_bytes().into()),
+        Value::Nil,
+    ]);
+    let result = PerMatch::<JsonPopped<Address>>::from_redis_value(val);
+    assert_eq!(
+        result,
+        Ok(PerMatch(vec![
+            JsonPopped(Some(Address {
+                street: "Downing".to_string()
+            })),
+            JsonPopped(None)
+        ]))
+    );
+
+    let val = Value::BulkString("2".as_bytes().into());
+    let result = JsonPopped::<u32>::from_redis_value(val);
+    assert_eq!(result, Ok(JsonPopped(Some(2))));
+}
+
+#[test]
+pub fn it_should_fail_if_the_popped_element_is_not_compatible() {
+    let val = Value::Array(vec![Value::BulkString("{\"street\":1}".as_bytes().into())]);
+    let result = PerMatch::<JsonPopped<Address>>::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to json_reply::Address with serde_json: invalid type: integer `1`, expected a string (at `street`, line 1 column 11). (response was bulk-string('\"{\\\"street\\\":1}\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_deserialize_incremented_numbers() {
+    let val = Value::BulkString("[2,null]".as_bytes().into());
+    let result = JsonAll::<Option<i64>>::from_redis_value(val);
+    assert_eq!(result, Ok(JsonAll(vec![Some(2), None])));
+}
@@ -1,8 +1,8 @@
-use redis::{FromRedisValue, Value};
-use redis_macros::Json;
-use serde::Deserialize;
+use redis::{FromRedisValue, ToRedisArgs, Value};
+use redis_macros::{from_json_reply, Json, JsonAll, JsonLegacy, JsonMSet, JsonMulti, JsonRef};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 enum Address {
     Street(String),
     Road(String),
@@ -62,7 +62,7 @@ pub fn it_should_fail_if_the_result_is_not_redis_json() {
     let val = Value::BulkString("{\"id\":1,\"name\":\"Ziggy\",\"addresses\":[{\"Street\":\"Downing\"},{\"Road\":\"Abbey\"}]}".as_bytes().into());
     let result = Json::<User>::from_redis_value(val);
     let err = result.unwrap_err();
-    assert_eq!(err.to_string(), "Incompatible type - Response type in JSON was not deserializable. (response was bulk-string('\"{\\\"id\\\":1,\\\"name\\\":\\\"Ziggy\\\",\\\"addresses\\\":[{\\\"Street\\\":\\\"Downing\\\"},{\\\"Road\\\":\\\"Abbey\\\"}]}\"'))".to_string());
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to json_wrapper::User with serde_json: expected a RedisJSON response wrapped in square brackets. (response was bulk-string('\"{\\\"id\\\":1,\\\"name\\\":\\\"Ziggy\\\",\\\"addresses\\\":[{\\\"Street\\\":\\\"Downing\\\"},{\\\"Road\\\":\\\"Abbey\\\"}]}\"'))".to_string());
 }
 
 #[test]
@@ -70,7 +70,7 @@ pub fn it_should_fail_if_input_is_not_compatible_with_type() {
     let val = Value::BulkString("[{}]".as_bytes().into());
     let result = Json::<User>::from_redis_value(val);
     let err = result.unwrap_err();
-    assert_eq!(err.to_string(), "Incompatible type - Response type in JSON could not be deserialized: missing field `id` at line 1 column 2 (response was bulk-string('\"[{}]\"'))".to_string());
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to json_wrapper::User with serde_json: missing field `id` (at `[0]`, line 1 column 3). (response was bulk-string('\"[{}]\"'))".to_string());
 }
 
 #[test]
@@ -78,7 +78,7 @@ pub fn it_should_fail_if_input_is_not_valid_utf8() {
     let val = Value::BulkString(vec![0, 159, 146, 150]); // Some invalid utf8
     let result = Json::<User>::from_redis_value(val);
     let err = result.unwrap_err();
-    assert_eq!(err.to_string(), "Incompatible type - Response type in JSON is invalid UTF-8: invalid utf-8 sequence of 1 bytes from index 1. (response was binary-data([0, 159, 146, 150]))".to_string());
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to json_wrapper::User with serde_json: invalid UTF-8: invalid utf-8 sequence of 1 bytes from index 1. (response was binary-data([0, 159, 146, 150]))".to_string());
 }
 
 #[test]
@@ -88,7 +88,185 @@ pub fn it_should_fail_if_input_is_missing() {
     let err = result.unwrap_err();
     assert_eq!(
         err.to_string(),
-        "Incompatible type - Response type in JSON was not deserializable. (response was nil)"
+        "Incompatible type - Response type not deserializable to json_wrapper::User with serde_json: expected a bulk string. (response was nil)"
             .to_string()
     );
 }
+
+#[test]
+pub fn it_should_serialize_json_wrapper_to_a_single_argument() {
+    let addresses = Json(vec![
+        Address::Street("Downing".to_string()),
+        Address::Road("Abbey".to_string()),
+    ]);
+    let bytes = addresses.to_redis_args();
+    assert_eq!(bytes.len(), 1);
+    assert_eq!(
+        bytes[0],
+        "[{\"Street\":\"Downing\"},{\"Road\":\"Abbey\"}]".as_bytes()
+    );
+    assert_eq!(
+        serde_json::to_string(&addresses).unwrap(),
+        "[{\"Street\":\"Downing\"},{\"Road\":\"Abbey\"}]"
+    );
+}
+
+#[test]
+pub fn it_should_serialize_json_ref_without_cloning() {
+    let name = "Ziggy".to_string();
+    let bytes = JsonRef(&name).to_redis_args();
+    assert_eq!(bytes, vec!["\"Ziggy\"".as_bytes().to_vec()]);
+
+    let ids: &[u32] = &[1, 2, 3];
+    let bytes = JsonRef(ids).to_redis_args();
+    assert_eq!(bytes, vec!["[1,2,3]".as_bytes().to_vec()]);
+}
+
+#[test]
+pub fn it_should_behave_as_a_newtype() {
+    let mut ids: Json<Vec<u32>> = vec![1, 2].into();
+    ids.push(3);
+    assert_eq!(ids.len(), 3);
+    assert_eq!(ids.clone(), Json(vec![1, 2, 3]));
+    assert_eq!(ids.into_inner(), vec![1, 2, 3]);
+
+    let parsed: Json<Vec<u32>> = serde_json::from_str("[1,2,3]").unwrap();
+    assert_eq!(parsed, Json(vec![1, 2, 3]));
+}
+
+#[test]
+pub fn it_should_fail_if_there_are_multiple_matches() {
+    let val = Value::BulkString("[1,2]".as_bytes().into());
+    let result = Json::<u32>::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to u32 with serde_json: expected exactly one match, got 2. (response was bulk-string('\"[1,2]\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_fail_if_there_are_no_matches() {
+    let val = Value::BulkString("[]".as_bytes().into());
+    let result = Json::<u32>::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to u32 with serde_json: expected exactly one match, got 0. (response was bulk-string('\"[]\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_deserialize_every_match() {
+    let val = Value::BulkString("[1,2]".as_bytes().into());
+    let result = JsonAll::<u32>::from_redis_value(val);
+    assert_eq!(result, Ok(JsonAll(vec![1, 2])));
+
+    let val = Value::BulkString("[]".as_bytes().into());
+    let result = JsonAll::<u32>::from_redis_value(val);
+    assert_eq!(result, Ok(JsonAll(vec![])));
+}
+
+#[test]
+pub fn it_should_fail_if_one_of_the_matches_is_not_compatible() {
+    let val = Value::BulkString("[1,\"2\"]".as_bytes().into());
+    let result = JsonAll::<u32>::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to u32 with serde_json: invalid type: string \"2\", expected u32 (at `[1]`, line 1 column 6). (response was bulk-string('\"[1,\\\"2\\\"]\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_deserialize_legacy_path_results() {
+    let val = Value::BulkString(
+        "{\"id\":1,\"name\":\"Ziggy\",\"addresses\":[{\"Street\":\"Downing\"}]}"
+            .as_bytes()
+            .into(),
+    );
+    let result = JsonLegacy::<User>::from_redis_value(val);
+    assert_eq!(
+        result.map(JsonLegacy::into_inner),
+        Ok(User {
+            id: 1,
+            name: "Ziggy".to_string(),
+            addresses: vec![Address::Street("Downing".to_string())],
+        })
+    );
+
+    let val = Value::BulkString("[1,2]".as_bytes().into());
+    let result = JsonLegacy::<Vec<u32>>::from_redis_value(val);
+    assert_eq!(result, Ok(JsonLegacy(vec![1, 2])));
+}
+
+#[test]
+pub fn it_should_fail_if_legacy_path_result_is_not_compatible_with_type() {
+    let val = Value::BulkString("\"Ziggy\"".as_bytes().into());
+    let result = JsonLegacy::<u32>::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to u32 with serde_json: invalid type: string \"Ziggy\", expected u32 (line 1 column 7). (response was bulk-string('\"\\\"Ziggy\\\"\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_decide_from_the_path_syntax() {
+    let val = Value::BulkString("[1]".as_bytes().into());
+    assert_eq!(from_json_reply::<u32>("$.id", val), Ok(1));
+
+    let val = Value::BulkString("1".as_bytes().into());
+    assert_eq!(from_json_reply::<u32>(".id", val), Ok(1));
+
+    let val = Value::BulkString("[1]".as_bytes().into());
+    assert_eq!(from_json_reply::<Vec<u32>>("ids", val), Ok(vec![1]));
+}
+
+#[test]
+pub fn it_should_deserialize_the_value_of_every_key() {
+    let val = Value::Array(vec![
+        Value::BulkString("[1]".as_bytes().into()),
+        Value::Nil,
+        Value::BulkString("[3]".as_bytes().into()),
+    ]);
+    let result = JsonMulti::<u32>::from_redis_value(val);
+    assert_eq!(result, Ok(JsonMulti(vec![Some(1), None, Some(3)])));
+}
+
+#[test]
+pub fn it_should_fail_with_the_index_of_the_failing_key() {
+    let val = Value::Array(vec![
+        Value::BulkString("[1]".as_bytes().into()),
+        Value::BulkString("[\"2\"]".as_bytes().into()),
+    ]);
+    let result = JsonMulti::<u32>::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to u32 with serde_json: at key 1: invalid type: string \"2\", expected u32 (at `[0]`, line 1 column 4). (response was bulk-string('\"[\\\"2\\\"]\"'))".to_string());
+
+    let val = Value::Array(vec![Value::BulkString("[1,2]".as_bytes().into())]);
+    let result = JsonMulti::<u32>::from_redis_value(val);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to u32 with serde_json: at key 0: expected exactly one match, got 2. (response was bulk-string('\"[1,2]\"'))".to_string());
+}
+
+#[test]
+pub fn it_should_fail_if_the_multi_key_result_is_not_an_array() {
+    let result = JsonMulti::<u32>::from_redis_value(Value::Nil);
+    let err = result.unwrap_err();
+    assert_eq!(err.to_string(), "Incompatible type - Response type not deserializable to u32 with serde_json: expected an array. (response was nil)".to_string());
+}
+
+#[test]
+pub fn it_should_build_json_mset_arguments() {
+    let (first, second) = (vec![1, 2], vec![3]);
+    let mset = JsonMSet::new()
+        .set("user:1", "$.ids", &first)
+        .set("user:2", "$", &second);
+    assert_eq!(mset.len(), 2);
+    assert_eq!(
+        mset.to_redis_args(),
+        vec![
+            b"user:1".to_vec(),
+            b"$.ids".to_vec(),
+            b"[1,2]".to_vec(),
+            b"user:2".to_vec(),
+            b"$".to_vec(),
+            b"[3]".to_vec(),
+        ]
+    );
+
+    let mset: JsonMSet<_, _, str> = [("user:1", "$.name", "Ziggy")].into_iter().collect();
+    assert_eq!(
+        mset.cmd().get_packed_command(),
+        b"*4\r\n$9\r\nJSON.MSET\r\n$6\r\nuser:1\r\n$6\r\n$.name\r\n$7\r\n\"Ziggy\"\r\n".to_vec()
+    );
+}
@@ -0,0 +1,131 @@
+use redis::{FromRedisValue, ToRedisArgs, Value};
+use redis_macros::{FromRedisValue, Lazy, RedisJsonPath, ToRedisArgs};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, RedisJsonPath)]
+struct History {
+    events: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRedisValue, ToRedisArgs, RedisJsonPath)]
+struct User {
+    id: u32,
+    history: Lazy<History>,
+}
+
+fn bulk_string(s: &str) -> Value {
+    Value::BulkString(s.as_bytes().to_vec())
+}
+
+#[test]
+pub fn it_should_decode_on_first_access() {
+    let user = User::from_redis_value(bulk_string(
+        "{\"id\":1,\"history\":{ \"events\": [\"login\"] }}",
+    ))
+    .unwrap();
+    assert_eq!(user.id, 1);
+    assert!(!user.history.is_decoded());
+    assert_eq!(user.history.raw_json(), Some("{ \"events\": [\"login\"] }"));
+
+    assert_eq!(user.history.get().unwrap().events, vec!["login"]);
+    assert!(user.history.is_decoded());
+}
+
+#[test]
+pub fn it_should_write_unchanged_values_verbatim() {
+    let user = User::from_redis_value(bulk_string(
+        "{\"id\":1,\"history\":{ \"events\": [\"login\"] }}",
+    ))
+    .unwrap();
+    user.history.get().unwrap();
+    assert_eq!(
+        user.to_redis_args(),
+        vec![b"{\"id\":1,\"history\":{ \"events\": [\"login\"] }}".to_vec()]
+    );
+}
+
+#[test]
+pub fn it_should_serialize_changed_values() {
+    let mut user = User::from_redis_value(bulk_string(
+        "{\"id\":1,\"history\":{ \"events\": [\"login\"] }}",
+    ))
+    .unwrap();
+    user.history
+        .get_mut()
+        .unwrap()
+        .events
+        .push("logout".to_string());
+    assert_eq!(user.history.raw_json(), None);
+    assert_eq!(
+        user.to_redis_args(),
+        vec![b"{\"id\":1,\"history\":{\"events\":[\"login\",\"logout\"]}}".to_vec()]
+    );
+
+    let user = User {
+        id: 2,
+        history: Lazy::new(History { events: vec![] }),
+    };
+    assert_eq!(
+        user.to_redis_args(),
+        vec![b"{\"id\":2,\"history\":{\"events\":[]}}".to_vec()]
+    );
+}
+
+#[test]
+pub fn it_should_read_redis_json_replies() {
+    let user =
+        User::from_redis_value(bulk_string("[{\"id\":1,\"history\":{\"events\":[]}}]")).unwrap();
+    assert_eq!(
+        user.history.into_inner().unwrap(),
+        History { events: vec![] }
+    );
+}
+
+#[test]
+pub fn it_should_read_json_wrappers() {
+    let redis_macros::Json(history) = redis_macros::Json::<Lazy<History>>::from_redis_value(
+        bulk_string("[{\"events\":[\"login\"]}]"),
+    )
+    .unwrap();
+    assert_eq!(history.raw_json(), Some("{\"events\":[\"login\"]}"));
+}
+
+#[test]
+pub fn it_should_fail_on_access_with_the_path() {
+    let user =
+        User::from_redis_value(bulk_string("{\"id\":1,\"history\":{\"events\":[1]}}")).unwrap();
+    let err = user.history.get().unwrap_err();
+    assert_eq!(err.path(), Some("events[0]"));
+    assert!(err.to_string().contains("History"), "{err}");
+}
+
+#[test]
+pub fn it_should_use_the_path_of_the_inner_type() {
+    assert_eq!(User::path().history().events().as_str(), "$.history.events");
+}
+
+#[test]
+pub fn it_should_not_show_the_raw_json_in_errors() {
+    let user = User::from_redis_value(bulk_string(
+        "{\"id\":1,\"history\":{\"events\":[1],\"token\":\"hunter2\"}}",
+    ))
+    .unwrap();
+    let err = user.history.get().unwrap_err().to_string();
+    assert!(!err.contains("hunter2"), "{err}");
+}
+
+#[test]
+pub fn it_should_decode_for_other_serializers() {
+    let user = User::from_redis_value(bulk_string(
+        "{\"id\":1,\"history\":{ \"events\": [\"login\"] }}",
+    ))
+    .unwrap();
+    assert_eq!(
+        serde_yaml::to_string(&user).unwrap(),
+        "id: 1\nhistory:\n  events:\n  - login\n"
+    );
+    assert_eq!(
+        serde_json::to_value(&user).unwrap(),
+        serde_json::json!({"id": 1, "history": {"events": ["login"]}})
+    );
+}
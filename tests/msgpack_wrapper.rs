@@ -0,0 +1,74 @@
+#![cfg(feature = "msgpack")]
+
+use redis::{FromRedisValue, ToRedisArgs, Value};
+use redis_macros::MsgPack;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Address {
+    Street(String),
+    Road(String),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct User {
+    id: u32,
+    name: String,
+    addresses: Vec<Address>,
+}
+
+fn user() -> User {
+    User {
+        id: 1,
+        name: "Ziggy".to_string(),
+        addresses: vec![
+            Address::Street("Downing".to_string()),
+            Address::Road("Abbey".to_string()),
+        ],
+    }
+}
+
+#[test]
+pub fn it_should_roundtrip_the_value() {
+    let args = MsgPack(user()).to_redis_args();
+    assert_eq!(args.len(), 1);
+    let MsgPack(parsed) =
+        MsgPack::<User>::from_redis_value(Value::BulkString(args[0].clone())).unwrap();
+    assert_eq!(parsed, user());
+}
+
+#[test]
+pub fn it_should_roundtrip_foreign_types() {
+    let map = BTreeMap::from([("requests".to_string(), vec![1u32, 2])]);
+    let args = MsgPack(&map).to_redis_args();
+    let parsed =
+        MsgPack::<BTreeMap<String, Vec<u32>>>::from_redis_value(Value::BulkString(args[0].clone()))
+            .unwrap()
+            .into_inner();
+    assert_eq!(parsed, map);
+}
+
+#[test]
+pub fn it_should_fail_with_the_path_to_the_field() {
+    let invalid = BTreeMap::from([("id", "one")]);
+    let args = MsgPack(&invalid).to_redis_args();
+    let err = MsgPack::<User>::from_redis_value(Value::BulkString(args[0].clone()))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("rmp_serde"), "{err}");
+    assert!(err.contains("(at `id`"), "{err}");
+}
+
+#[test]
+pub fn it_should_fail_on_other_values() {
+    let err = MsgPack::<User>::from_redis_value(Value::Int(1))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("rmp_serde"), "{err}");
+    assert!(MsgPack::<User>::from_redis_value(Value::Nil).is_err());
+    assert_eq!(
+        Option::<MsgPack<User>>::from_redis_value(Value::Nil),
+        Ok(None)
+    );
+}
@@ -0,0 +1,10 @@
+use redis_macros::FromRedisValue;
+use serde::Deserialize;
+
+#[derive(Deserialize, FromRedisValue)]
+#[redis_bound(deserialise = "")]
+struct Id<T> {
+    id: T,
+}
+
+fn main() {}
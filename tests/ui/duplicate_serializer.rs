@@ -0,0 +1,11 @@
+use redis_macros::FromRedisValue;
+use serde::Deserialize;
+
+#[derive(Deserialize, FromRedisValue)]
+#[redis_serializer(serde_json)]
+#[redis_serializer(serde_yaml)]
+struct User {
+    id: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use redis_macros::{FromRedisValue, ToRedisArgs};
+
+#[derive(FromRedisValue, ToRedisArgs)]
+#[redis_enum(repr = f32)]
+enum Status {
+    Active,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use redis_macros::{FromRedisValue, ToRedisArgs};
+
+#[derive(FromRedisValue, ToRedisArgs)]
+#[redis_enum(repr = u8)]
+enum Priority {
+    Low = 254,
+    Medium,
+    High,
+}
+
+fn main() {}
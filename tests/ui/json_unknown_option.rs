@@ -0,0 +1,10 @@
+use redis_macros::FromRedisValue;
+use serde::Deserialize;
+
+#[derive(Deserialize, FromRedisValue)]
+#[redis_json(bracket = "off")]
+struct User {
+    id: u32,
+}
+
+fn main() {}
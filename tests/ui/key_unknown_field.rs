@@ -0,0 +1,10 @@
+use redis_macros::ToRedisArgs;
+use serde::Serialize;
+
+#[derive(Serialize, ToRedisArgs)]
+#[redis_key = "user:{uid}"]
+struct User {
+    id: u32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use redis_macros::FromRedisValue;
+use serde::Deserialize;
+
+#[derive(Deserialize, FromRedisValue)]
+struct User {
+    #[redis_redact(always)]
+    password: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use redis_macros::FromRedisValue;
+use serde::Deserialize;
+
+#[derive(Deserialize, FromRedisValue)]
+#[redis_crate(deadpool_redis::redis)]
+struct User {
+    id: u32,
+}
+
+fn main() {}
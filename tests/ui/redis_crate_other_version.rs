@@ -0,0 +1,10 @@
+use redis_macros::FromRedisValue;
+use serde::Deserialize;
+
+#[derive(Deserialize, FromRedisValue)]
+#[redis_crate = "redis_old"]
+struct User {
+    id: u32,
+}
+
+fn main() {}
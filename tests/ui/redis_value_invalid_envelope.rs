@@ -0,0 +1,10 @@
+use redis_macros::RedisValue;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, RedisValue)]
+#[redis(envelope(version = 1))]
+struct User {
+    id: u32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use redis_macros::FromRedisValue;
+use serde::Deserialize;
+
+#[derive(Deserialize, FromRedisValue)]
+#[redis_serializer(serde_json, fallbacks(serde_yaml))]
+struct User {
+    id: u32,
+}
+
+fn main() {}
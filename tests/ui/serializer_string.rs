@@ -0,0 +1,10 @@
+use redis_macros::{FromRedisValue, ToRedisArgs};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, FromRedisValue, ToRedisArgs)]
+#[redis_serializer("serde_yaml")]
+struct User {
+    id: u32,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use redis_macros::FromRedisValue;
+
+#[derive(FromRedisValue)]
+#[redis_transparent]
+#[redis_serializer(serde_yaml)]
+struct UserId(u64);
+
+fn main() {}
@@ -0,0 +1,9 @@
+use redis_macros::FromRedisValue;
+
+#[derive(FromRedisValue)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
@@ -0,0 +1,78 @@
+#![cfg(feature = "yaml")]
+
+use redis::{FromRedisValue, ToRedisArgs, Value};
+use redis_macros::Yaml;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Address {
+    Street(String),
+    Road(String),
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct User {
+    id: u32,
+    name: String,
+    addresses: Vec<Address>,
+}
+
+fn user() -> User {
+    User {
+        id: 1,
+        name: "Ziggy".to_string(),
+        addresses: vec![
+            Address::Street("Downing".to_string()),
+            Address::Road("Abbey".to_string()),
+        ],
+    }
+}
+
+#[test]
+pub fn it_should_roundtrip_the_value() {
+    let args = Yaml(user()).to_redis_args();
+    assert_eq!(args.len(), 1);
+    let Yaml(parsed) = Yaml::<User>::from_redis_value(Value::BulkString(args[0].clone())).unwrap();
+    assert_eq!(parsed, user());
+}
+
+#[test]
+pub fn it_should_roundtrip_foreign_types() {
+    let map = BTreeMap::from([("requests".to_string(), vec![1u32, 2])]);
+    let args = Yaml(&map).to_redis_args();
+    let parsed =
+        Yaml::<BTreeMap<String, Vec<u32>>>::from_redis_value(Value::BulkString(args[0].clone()))
+            .unwrap()
+            .into_inner();
+    assert_eq!(parsed, map);
+}
+
+#[test]
+pub fn it_should_fail_with_the_path_to_the_field() {
+    let invalid = BTreeMap::from([("id", "one")]);
+    let args = Yaml(&invalid).to_redis_args();
+    let err = Yaml::<User>::from_redis_value(Value::BulkString(args[0].clone()))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("serde_yaml"), "{err}");
+    assert!(err.contains("(at `id`"), "{err}");
+}
+
+#[test]
+pub fn it_should_fail_on_other_values() {
+    let err = Yaml::<User>::from_redis_value(Value::Int(1))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("serde_yaml"), "{err}");
+    assert!(Yaml::<User>::from_redis_value(Value::Nil).is_err());
+    assert_eq!(Option::<Yaml<User>>::from_redis_value(Value::Nil), Ok(None));
+}
+
+#[test]
+pub fn it_should_write_yaml() {
+    assert_eq!(
+        Yaml(user()).to_redis_args(),
+        vec![b"id: 1\nname: Ziggy\naddresses:\n- !Street Downing\n- !Road Abbey\n".to_vec()]
+    );
+}